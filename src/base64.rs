@@ -0,0 +1,66 @@
+// Standard padded base64, as used for header values that aren't valid UTF-8.
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const PAD: u8 = b'=';
+
+pub(crate) fn encode(input: &[u8]) -> String {
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push(PAD as char);
+            }
+        }
+    }
+    out
+}
+
+fn decode_symbol(symbol: u8) -> Option<u32> {
+    let value = match symbol {
+        b'A'..=b'Z' => symbol - b'A',
+        b'a'..=b'z' => symbol - b'a' + 26,
+        b'0'..=b'9' => symbol - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => return None,
+    };
+    Some(value.into())
+}
+
+pub(crate) fn decode(input: &str) -> Result<Vec<u8>, &'static str> {
+    const INVALID: &str = "invalid base64";
+
+    let input = input.as_bytes();
+    // `is_multiple_of` needs a newer Rust than the crate does.
+    #[allow(clippy::manual_is_multiple_of)]
+    if input.len() % 4 != 0 {
+        return Err(INVALID);
+    }
+
+    let mut out = Vec::with_capacity(input.len() / 4 * 3);
+    let chunks = input.len() / 4;
+    for (index, chunk) in input.chunks(4).enumerate() {
+        let padding = chunk.iter().rev().take_while(|b| **b == PAD).count();
+        if padding > 2 || (padding > 0 && index + 1 != chunks) {
+            return Err(INVALID);
+        }
+
+        let mut n = 0;
+        for symbol in &chunk[..4 - padding] {
+            n = n << 6 | decode_symbol(*symbol).ok_or(INVALID)?;
+        }
+        n <<= 6 * padding;
+
+        let bytes = n.to_be_bytes();
+        out.extend_from_slice(&bytes[1..4 - padding]);
+    }
+    Ok(out)
+}
@@ -0,0 +1,471 @@
+// Cookie and Set-Cookie values as objects.
+//
+// Human-readable formats write a `Cookie` value as a sequence of `{"name", "value"}` objects
+// and a `Set-Cookie` value as a `{"name", "value", "attributes"}` object, with the attributes
+// in order and `true` for those without a value. Values are only written this way when they
+// can be rebuilt byte for byte and no attribute is repeated, and are written as by
+// `header_value` otherwise.
+
+use std::fmt;
+
+use http::{
+    header::{COOKIE, SET_COOKIE},
+    HeaderName, HeaderValue,
+};
+use serde::{
+    de::{self, DeserializeSeed},
+    ser::{SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{base64, limits};
+
+const SEPARATOR: &str = "; ";
+const NAME_KEY: &str = "name";
+const VALUE_KEY: &str = "value";
+const ATTRIBUTES_KEY: &str = "attributes";
+const BASE64_KEY: &str = "base64";
+const KEYS: &[&str] = &[NAME_KEY, VALUE_KEY, ATTRIBUTES_KEY, BASE64_KEY];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Cookie,
+    SetCookie,
+}
+
+impl Kind {
+    pub(crate) fn of(name: &HeaderName) -> Option<Kind> {
+        if name == COOKIE {
+            Some(Kind::Cookie)
+        } else if name == SET_COOKIE {
+            Some(Kind::SetCookie)
+        } else {
+            None
+        }
+    }
+}
+
+type Pair<'a> = (&'a str, &'a str);
+
+struct SetCookie<'a> {
+    pair: Pair<'a>,
+    attributes: Vec<(&'a str, Option<&'a str>)>,
+}
+
+fn is_part(part: &str) -> bool {
+    !part.contains(';')
+}
+
+// Cookie names and attribute keys are tokens (RFC 6265, RFC 9110 section 5.6.2).
+fn is_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+fn parse_pair(part: &str) -> Option<Pair<'_>> {
+    let (name, value) = part.split_once('=')?;
+    (is_key(name) && is_part(value)).then_some((name, value))
+}
+
+// `None` unless the value would be rebuilt byte for byte.
+fn parse_cookie(value: &str) -> Option<Vec<Pair<'_>>> {
+    if value.is_empty() {
+        return Some(Vec::new());
+    }
+    value.split(SEPARATOR).map(parse_pair).collect()
+}
+
+fn parse_set_cookie(value: &str) -> Option<SetCookie<'_>> {
+    let mut parts = value.split(SEPARATOR);
+    let pair = parse_pair(parts.next()?)?;
+    let attributes: Vec<_> = parts
+        .map(|part| match part.split_once('=') {
+            Some((key, value)) => (is_key(key) && is_part(value)).then_some((key, Some(value))),
+            None => is_key(part).then_some((part, None)),
+        })
+        .collect::<Option<_>>()?;
+    // The attributes are written as a map, which can't hold a key twice.
+    let repeated = attributes
+        .iter()
+        .enumerate()
+        .any(|(i, (key, _))| attributes[..i].iter().any(|(other, _)| other == key));
+    (!repeated).then_some(SetCookie { pair, attributes })
+}
+
+// ===== Serializing =====
+
+struct PairWrapper<'a>(Pair<'a>);
+
+impl<'a> Serialize for PairWrapper<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let mut map = ser.serialize_map(Some(2))?;
+        map.serialize_entry(NAME_KEY, self.0 .0)?;
+        map.serialize_entry(VALUE_KEY, self.0 .1)?;
+        map.end()
+    }
+}
+
+struct AttributesWrapper<'a>(&'a [(&'a str, Option<&'a str>)]);
+
+impl<'a> Serialize for AttributesWrapper<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let mut map = ser.serialize_map(Some(self.0.len()))?;
+        for (key, value) in self.0 {
+            match value {
+                Some(value) => map.serialize_entry(key, value)?,
+                None => map.serialize_entry(key, &true)?,
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'a> Serialize for SetCookie<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let len = 2 + usize::from(!self.attributes.is_empty());
+        let mut map = ser.serialize_map(Some(len))?;
+        map.serialize_entry(NAME_KEY, self.pair.0)?;
+        map.serialize_entry(VALUE_KEY, self.pair.1)?;
+        if !self.attributes.is_empty() {
+            map.serialize_entry(ATTRIBUTES_KEY, &AttributesWrapper(&self.attributes))?;
+        }
+        map.end()
+    }
+}
+
+struct ValueWrapper<'a> {
+    kind: Kind,
+    value: &'a HeaderValue,
+}
+
+impl<'a> Serialize for ValueWrapper<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        if let Ok(value) = self.value.to_str() {
+            match self.kind {
+                Kind::Cookie => {
+                    if let Some(pairs) = parse_cookie(value) {
+                        return ser.collect_seq(pairs.into_iter().map(PairWrapper));
+                    }
+                }
+                Kind::SetCookie => {
+                    if let Some(cookie) = parse_set_cookie(value) {
+                        return cookie.serialize(ser);
+                    }
+                }
+            }
+        }
+        crate::header_value::serialize(self.value, ser)
+    }
+}
+
+// Serializes the values of a `Cookie` or `Set-Cookie` header in the human-readable
+// representation, as a sequence when there are several.
+pub(crate) fn serialize_values<S>(
+    kind: Kind,
+    values: &[&HeaderValue],
+    ser: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match values {
+        [value] => ValueWrapper { kind, value }.serialize(ser),
+        values => {
+            let mut seq = ser.serialize_seq(Some(values.len()))?;
+            for value in values {
+                seq.serialize_element(&ValueWrapper { kind, value })?;
+            }
+            seq.end()
+        }
+    }
+}
+
+// ===== Deserializing =====
+
+fn header_value<E: de::Error>(value: String) -> Result<HeaderValue, E> {
+    limits::check_header_value_len(value.len())?;
+    HeaderValue::try_from(value).map_err(de::Error::custom)
+}
+
+// An attribute value, `true` for attributes without one.
+struct AttributeValue(Option<String>);
+
+impl<'de> Deserialize<'de> for AttributeValue {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = AttributeValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string or `true`")
+            }
+
+            fn visit_bool<E: de::Error>(self, val: bool) -> Result<Self::Value, E> {
+                if val {
+                    Ok(AttributeValue(None))
+                } else {
+                    Err(E::invalid_value(de::Unexpected::Bool(val), &self))
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+                Ok(AttributeValue(Some(val.to_owned())))
+            }
+
+            fn visit_string<E: de::Error>(self, val: String) -> Result<Self::Value, E> {
+                Ok(AttributeValue(Some(val)))
+            }
+        }
+
+        de.deserialize_any(Visitor)
+    }
+}
+
+struct Attributes(Vec<(String, Option<String>)>);
+
+impl<'de> Deserialize<'de> for Attributes {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Attributes;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("cookie attributes")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: de::MapAccess<'de>,
+            {
+                let mut attributes = Vec::with_capacity(limits::capacity(map.size_hint()));
+                while let Some((key, value)) = map.next_entry::<String, AttributeValue>()? {
+                    attributes.push((key, value.0));
+                }
+                Ok(Attributes(attributes))
+            }
+        }
+
+        de.deserialize_map(Visitor)
+    }
+}
+
+// One element of a deserialized value: a cookie pair or a `Set-Cookie` value, a whole
+// `Cookie` value, or a value in the `header_value` representation.
+enum Element {
+    Pair(String, String),
+    SetCookie(HeaderValue),
+    Cookie(HeaderValue),
+    Raw(HeaderValue),
+}
+
+#[derive(Clone, Copy)]
+struct ElementVisitor {
+    kind: Kind,
+    // Whether a `Cookie` value may be a sequence of pairs here.
+    nested: bool,
+}
+
+impl<'de> de::Visitor<'de> for ElementVisitor {
+    type Value = Element;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self.kind {
+            Kind::Cookie => "a cookie header value",
+            Kind::SetCookie => "a set-cookie header value",
+        })
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        self.visit_string(val.to_owned())
+    }
+
+    fn visit_string<E: de::Error>(self, val: String) -> Result<Self::Value, E> {
+        header_value(val).map(Element::Raw)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        if self.kind != Kind::Cookie || !self.nested {
+            return Err(de::Error::invalid_type(de::Unexpected::Seq, &self));
+        }
+        let mut pairs = Vec::new();
+        let seed = ElementSeed(ElementVisitor {
+            nested: false,
+            ..self
+        });
+        while let Some(element) = seq.next_element_seed(seed)? {
+            match element {
+                Element::Pair(name, value) => pairs.push(format!("{name}={value}")),
+                _ => return Err(de::Error::custom("expected a cookie name and value")),
+            }
+        }
+        header_value(pairs.join(SEPARATOR)).map(Element::Cookie)
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        let mut name = None;
+        let mut value = None;
+        let mut attributes = None;
+        let mut bytes = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                NAME_KEY if name.is_none() => name = Some(map.next_value::<String>()?),
+                VALUE_KEY if value.is_none() => value = Some(map.next_value::<String>()?),
+                ATTRIBUTES_KEY if self.kind == Kind::SetCookie && attributes.is_none() => {
+                    attributes = Some(map.next_value::<Attributes>()?.0);
+                }
+                ATTRIBUTES_KEY if self.kind == Kind::Cookie => {
+                    return Err(de::Error::unknown_field(
+                        &key,
+                        &[NAME_KEY, VALUE_KEY, BASE64_KEY],
+                    ));
+                }
+                BASE64_KEY if bytes.is_none() => {
+                    let val = base64::decode(&map.next_value::<String>()?);
+                    bytes = Some(val.map_err(de::Error::custom)?);
+                }
+                NAME_KEY | VALUE_KEY | ATTRIBUTES_KEY | BASE64_KEY => {
+                    return Err(de::Error::custom(format!("duplicate field `{key}`")));
+                }
+                _ => return Err(de::Error::unknown_field(&key, KEYS)),
+            }
+        }
+
+        match (name, value, attributes, bytes) {
+            (None, None, None, Some(bytes)) => {
+                limits::check_header_value_len(bytes.len())?;
+                HeaderValue::from_bytes(&bytes)
+                    .map(Element::Raw)
+                    .map_err(de::Error::custom)
+            }
+            (Some(name), Some(value), attributes, None) => {
+                // `Option::is_none_or` is too recent for the crate's MSRV.
+                #[allow(clippy::unnecessary_map_or)]
+                let valid = is_key(&name)
+                    && is_part(&value)
+                    && attributes
+                        .iter()
+                        .flatten()
+                        .all(|(key, value)| is_key(key) && value.as_deref().map_or(true, is_part));
+                if !valid {
+                    return Err(de::Error::custom(format!("invalid cookie {name}")));
+                }
+                if self.kind == Kind::Cookie {
+                    return Ok(Element::Pair(name, value));
+                }
+                let mut cookie = format!("{name}={value}");
+                for (key, value) in attributes.into_iter().flatten() {
+                    cookie.push_str(SEPARATOR);
+                    cookie.push_str(&key);
+                    if let Some(value) = value {
+                        cookie.push('=');
+                        cookie.push_str(&value);
+                    }
+                }
+                header_value(cookie).map(Element::SetCookie)
+            }
+            _ => Err(de::Error::custom(
+                "expected `name` and `value`, or `base64`",
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ElementSeed(ElementVisitor);
+
+impl<'de> DeserializeSeed<'de> for ElementSeed {
+    type Value = Element;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(self.0)
+    }
+}
+
+// Deserializes the human-readable representation of a `Cookie` or `Set-Cookie` header into
+// its values.
+#[derive(Clone, Copy)]
+pub(crate) struct ValuesSeed {
+    pub(crate) kind: Kind,
+}
+
+struct ValuesVisitor {
+    kind: Kind,
+}
+
+impl<'de> de::Visitor<'de> for ValuesVisitor {
+    type Value = Vec<HeaderValue>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self.kind {
+            Kind::Cookie => "cookie header values",
+            Kind::SetCookie => "set-cookie header values",
+        })
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        Ok(vec![header_value(val.to_owned())?])
+    }
+
+    fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        let visitor = ElementVisitor {
+            kind: self.kind,
+            nested: false,
+        };
+        match de::Visitor::visit_map(visitor, map)? {
+            Element::SetCookie(value) | Element::Raw(value) => Ok(vec![value]),
+            _ => Err(de::Error::invalid_type(de::Unexpected::Map, &self)),
+        }
+    }
+
+    // A `Cookie` value is a sequence of pairs, so a sequence holds either the pairs of a
+    // single value or several values.
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let seed = ElementSeed(ElementVisitor {
+            kind: self.kind,
+            nested: true,
+        });
+        let mut pairs = Vec::new();
+        let mut values = Vec::new();
+        while let Some(element) = seq.next_element_seed(seed)? {
+            match element {
+                Element::Pair(name, value) => pairs.push(format!("{name}={value}")),
+                Element::SetCookie(value) | Element::Cookie(value) | Element::Raw(value) => {
+                    values.push(value)
+                }
+            }
+        }
+
+        match (self.kind, pairs.is_empty(), values.is_empty()) {
+            (Kind::Cookie, _, true) => Ok(vec![header_value(pairs.join(SEPARATOR))?]),
+            (_, true, false) => Ok(values),
+            (Kind::SetCookie, true, true) => Err(de::Error::invalid_length(0, &self)),
+            _ => Err(de::Error::custom(
+                "expected either cookie pairs or cookie header values",
+            )),
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for ValuesSeed {
+    type Value = Vec<HeaderValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(ValuesVisitor { kind: self.kind })
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+
+use http::{
+    header::{Entry, OccupiedEntry},
+    HeaderMap, HeaderName,
+};
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+use crate::limits;
+
+/// What deserializing a [`HeaderMap`] does when a header name appears more than once.
+///
+/// Names are compared after parsing, so `Accept` and `accept` are the same header. The
+/// [`header_map`](crate::header_map) and [`header_map_generic`](crate::header_map_generic)
+/// modules [append](Self::Append), as do
+/// [`header_map::structured`](crate::header_map::structured),
+/// [`header_map::cookies`](crate::header_map::cookies) and
+/// [`header_map::original_case`](crate::header_map::original_case). Their `duplicate`
+/// submodules select another policy, and
+/// [`deserialize_header_map`](Self::deserialize_header_map) selects one at runtime.
+///
+/// Up to 1.0.2, a repeated name given a single value replaced the earlier values, and one
+/// given a sequence of values was ignored. Use [`LastWins`](Self::LastWins) or
+/// [`FirstWins`](Self::FirstWins) for maps that relied on either.
+///
+/// ```
+/// use http::HeaderMap;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     #[serde(with = "http_serde_ext::header_map::duplicate::error")]
+///     headers: HeaderMap,
+/// }
+///
+/// let yaml = "headers:\n  Accept: text/html\n  accept: '*/*'\n";
+/// let err = serde_yaml::from_str::<Config>(yaml).err().unwrap();
+/// assert!(err.to_string().contains("duplicate header accept"));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// Fail to deserialize.
+    Error,
+    /// Append the values to the earlier ones.
+    #[default]
+    Append,
+    /// Replace the earlier values.
+    LastWins,
+    /// Ignore the later values.
+    FirstWins,
+}
+
+impl DuplicatePolicy {
+    /// Deserializes a [`HeaderMap`] in the [`header_map`](crate::header_map) representation
+    /// with this policy.
+    pub fn deserialize_header_map<'de, D>(self, de: D) -> Result<HeaderMap, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        crate::header_map::deserialize_with_policy(de, self)
+    }
+
+    /// Deserializes a [`HeaderMap<T>`] in the
+    /// [`header_map_generic`](crate::header_map_generic) representation with this policy.
+    pub fn deserialize_header_map_generic<'de, D, T>(self, de: D) -> Result<HeaderMap<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: for<'a> Deserialize<'a>,
+    {
+        crate::header_map_generic::deserialize_with_policy(de, self)
+    }
+
+    // Inserts the values of one entry of the input.
+    pub(crate) fn insert<E, T>(
+        self,
+        map: &mut HeaderMap<T>,
+        key: HeaderName,
+        values: impl IntoIterator<Item = T>,
+    ) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        let mut values = values.into_iter();
+        self.insert_with(map, key, || Ok(values.next()))
+    }
+
+    // Inserts values as `next` yields them, so they can be decoded straight into the map.
+    // All values are consumed, even those the policy ignores.
+    fn insert_with<E, T>(
+        self,
+        map: &mut HeaderMap<T>,
+        key: HeaderName,
+        mut next: impl FnMut() -> Result<Option<T>, E>,
+    ) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        let Some(first) = next()? else {
+            return Err(de::Error::custom(format!("no value for header {key}")));
+        };
+
+        // The number of values in the map, counted here as the entry borrows it.
+        let mut len = map.len();
+
+        // With a `HeaderName` key, the only error is a map too full to take another name.
+        match map.try_entry(key).map_err(limits::header_map_full)? {
+            Entry::Vacant(e) => {
+                limits::check_headers(len)?;
+                len += 1;
+                let mut e = e.try_insert_entry(first).map_err(limits::header_map_full)?;
+                while let Some(val) = next()? {
+                    append(&mut e, &mut len, val)?;
+                }
+            }
+            Entry::Occupied(mut e) => match self {
+                DuplicatePolicy::Error => {
+                    return Err(de::Error::custom(format!("duplicate header {}", e.key())));
+                }
+                DuplicatePolicy::Append => {
+                    append(&mut e, &mut len, first)?;
+                    while let Some(val) = next()? {
+                        append(&mut e, &mut len, val)?;
+                    }
+                }
+                DuplicatePolicy::LastWins => {
+                    len -= e.iter().count() - 1;
+                    e.insert(first);
+                    while let Some(val) = next()? {
+                        append(&mut e, &mut len, val)?;
+                    }
+                }
+                DuplicatePolicy::FirstWins => while next()?.is_some() {},
+            },
+        }
+        Ok(())
+    }
+}
+
+// Decodes a sequence of `W`s and inserts them into `map` one by one, without collecting
+// them first.
+pub(crate) struct InsertSeed<'a, T, W> {
+    pub(crate) policy: DuplicatePolicy,
+    pub(crate) map: &'a mut HeaderMap<T>,
+    pub(crate) key: HeaderName,
+    pub(crate) unwrap: fn(W) -> T,
+}
+
+impl<'de, 'a, T, W: Deserialize<'de>> de::Visitor<'de> for InsertSeed<'a, T, W> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of values for header {}", self.key)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let unwrap = self.unwrap;
+        self.policy.insert_with(self.map, self.key, || {
+            Ok(seq.next_element::<W>()?.map(unwrap))
+        })
+    }
+}
+
+impl<'de, 'a, T, W: Deserialize<'de>> de::DeserializeSeed<'de> for InsertSeed<'a, T, W> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_seq(self)
+    }
+}
+
+fn append<E: de::Error, T>(e: &mut OccupiedEntry<T>, len: &mut usize, val: T) -> Result<(), E> {
+    limits::check_headers(*len)?;
+    *len += 1;
+    e.append(val);
+    Ok(())
+}
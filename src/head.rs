@@ -0,0 +1,226 @@
+use std::{fmt, marker::PhantomData};
+
+use http::{Extensions, HeaderMap, Method, StatusCode, Uri, Version};
+use serde::{de, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{header_map::Entries, DuplicatePolicy, HeaderCaseMap, RedactionPolicy};
+
+pub(crate) const HEAD_FIELD: &str = "head";
+pub(crate) const BODY_FIELD: &str = "body";
+
+// Writes a request or response as its head and body. Only the extensions written as part of
+// the head are allowed.
+pub(crate) fn serialize_message<S, H, T>(
+    name: &'static str,
+    head: &H,
+    extensions_len: usize,
+    extensions: &Extensions,
+    body: &T,
+    ser: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    H: Serialize,
+    T: Serialize,
+{
+    if extensions.len() > extensions_len {
+        return Err(serde::ser::Error::custom("extensions is not empty"));
+    }
+    let mut state = ser.serialize_struct(name, 2)?;
+    state.serialize_field(HEAD_FIELD, head)?;
+    state.serialize_field(BODY_FIELD, body)?;
+    state.end()
+}
+
+// The headers of a request or response head, written with their original spelling
+// when a case map is given, keeping the sensitivity of their values, with cookies as
+// objects, sorted by name, or redacted.
+pub(crate) struct BorrowedHeaders<'a> {
+    pub(crate) headers: &'a HeaderMap,
+    pub(crate) case: Option<&'a HeaderCaseMap>,
+    pub(crate) with_sensitive: bool,
+    pub(crate) redaction: Option<&'a RedactionPolicy>,
+    pub(crate) cookies: bool,
+    pub(crate) sorted: bool,
+}
+
+impl<'a> BorrowedHeaders<'a> {
+    pub(crate) fn extensions_len(&self) -> usize {
+        usize::from(self.case.is_some())
+    }
+}
+
+impl<'a> Serialize for BorrowedHeaders<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let headers = self.headers;
+        if let Some(policy) = self.redaction {
+            return crate::header_map::serialize_redacted(headers, policy, ser);
+        }
+        match self.case {
+            Some(case) => crate::header_map::original_case::serialize_with_case(headers, case, ser),
+            None if self.cookies => crate::header_map::cookies::serialize(headers, ser),
+            None if self.sorted => crate::header_map::sorted::serialize(headers, ser),
+            None => crate::header_map::serialize_with_sensitive(headers, self.with_sensitive, ser),
+        }
+    }
+}
+
+pub(crate) trait IntoHeaders {
+    fn into_parts(self) -> (HeaderMap, Option<HeaderCaseMap>);
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Headers(#[serde(with = "crate::header_map")] HeaderMap);
+
+impl IntoHeaders for Headers {
+    fn into_parts(self) -> (HeaderMap, Option<HeaderCaseMap>) {
+        (self.0, None)
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CasedHeaders(
+    #[serde(with = "crate::header_map::original_case")] (HeaderMap, HeaderCaseMap),
+);
+
+impl IntoHeaders for CasedHeaders {
+    // The case map is only kept when some name wasn't spelled in lowercase.
+    fn into_parts(self) -> (HeaderMap, Option<HeaderCaseMap>) {
+        let (headers, case) = self.0;
+        if case.is_lowercase() {
+            (headers, None)
+        } else {
+            (headers, Some(case))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RedactedHeaders(#[serde(with = "crate::header_map::redacted")] HeaderMap);
+
+impl IntoHeaders for RedactedHeaders {
+    fn into_parts(self) -> (HeaderMap, Option<HeaderCaseMap>) {
+        (self.0, None)
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CookieHeaders(#[serde(with = "crate::header_map::cookies")] HeaderMap);
+
+impl IntoHeaders for CookieHeaders {
+    fn into_parts(self) -> (HeaderMap, Option<HeaderCaseMap>) {
+        (self.0, None)
+    }
+}
+
+// Deserializers for the fields of a partial head, which are `None` when missing.
+pub(crate) mod some {
+    use super::*;
+
+    macro_rules! some {
+        ($($name:ident: $ty:ty => $path:path),+) => {
+            $(pub(crate) fn $name<'de, D: Deserializer<'de>>(de: D) -> Result<Option<$ty>, D::Error> {
+                $path(de).map(Some)
+            })+
+        };
+    }
+
+    some!(
+        method: Method => crate::method::deserialize,
+        uri: Uri => crate::uri::deserialize,
+        status: StatusCode => crate::status_code::deserialize,
+        version: Version => crate::version::deserialize,
+        headers: Entries => Entries::deserialize
+    );
+}
+
+// Inserts the headers of the input into `into` with `policy`, leaving it unchanged on error.
+pub(crate) fn merge_headers<E: de::Error>(
+    into: &mut HeaderMap,
+    from: Entries,
+    policy: DuplicatePolicy,
+) -> Result<(), E> {
+    let mut headers = into.clone();
+    from.insert_into(&mut headers, policy)?;
+    *into = headers;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Field {
+    Head,
+    Body,
+}
+
+struct PartialMessageVisitor<H, T> {
+    name: &'static str,
+    ph: PhantomData<(H, T)>,
+}
+
+impl<'de, H, T> de::Visitor<'de> for PartialMessageVisitor<H, T>
+where
+    H: Deserialize<'de>,
+    T: Deserialize<'de>,
+{
+    type Value = (Option<H>, Option<T>);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.name)
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: de::SeqAccess<'de>,
+    {
+        let head = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let body = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok((Some(head), Some(body)))
+    }
+
+    fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
+    where
+        V: de::MapAccess<'de>,
+    {
+        let mut head = None;
+        let mut body = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::Head if head.is_some() => {
+                    return Err(de::Error::duplicate_field(HEAD_FIELD));
+                }
+                Field::Head => head = Some(map.next_value()?),
+                Field::Body if body.is_some() => {
+                    return Err(de::Error::duplicate_field(BODY_FIELD));
+                }
+                Field::Body => body = Some(map.next_value()?),
+            }
+        }
+        Ok((head, body))
+    }
+}
+
+// Reads a request or response as its head and body, either of which may be missing in
+// human-readable formats.
+pub(crate) fn deserialize_partial_message<'de, D, H, T>(
+    name: &'static str,
+    de: D,
+) -> Result<(Option<H>, Option<T>), D::Error>
+where
+    D: Deserializer<'de>,
+    H: Deserialize<'de>,
+    T: Deserialize<'de>,
+{
+    de.deserialize_struct(
+        name,
+        &[HEAD_FIELD, BODY_FIELD],
+        PartialMessageVisitor {
+            name,
+            ph: PhantomData,
+        },
+    )
+}
@@ -0,0 +1,72 @@
+use http::{
+    header::{InvalidHeaderName, MaxSizeReached},
+    HeaderMap, HeaderName,
+};
+
+/// The original spellings of header names, in the order they were received.
+///
+/// [`http::HeaderName`] always normalizes names to lowercase. When present in the extensions
+/// of a [`Request`](http::Request) or [`Response`](http::Response), this map is used by
+/// [`request::original_case`](crate::request::original_case) and
+/// [`response::original_case`](crate::response::original_case) to write the names as they were
+/// originally spelled, and is filled in again when deserializing.
+///
+/// hyper keeps its own case map private, so this type has to be filled in by whoever parses
+/// the message, e.g. from the raw header lines.
+///
+/// ```
+/// use http::header::CONTENT_TYPE;
+/// use http_serde_ext::HeaderCaseMap;
+///
+/// let mut case = HeaderCaseMap::new();
+/// case.append("Content-Type").unwrap();
+/// assert_eq!(case.get_all(&CONTENT_TYPE).collect::<Vec<_>>(), ["Content-Type"]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeaderCaseMap(HeaderMap<String>);
+
+impl HeaderCaseMap {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records another occurrence of a header, spelled as `original`.
+    pub fn append(&mut self, original: &str) -> Result<(), InvalidHeaderName> {
+        let name = HeaderName::from_bytes(original.as_bytes())?;
+        self.0.append(name, original.to_owned());
+        Ok(())
+    }
+
+    /// Returns the recorded spellings of `name`, in the order they were appended.
+    pub fn get_all<'a>(&'a self, name: &HeaderName) -> impl Iterator<Item = &'a str> + 'a {
+        self.0.get_all(name).into_iter().map(String::as_str)
+    }
+
+    /// Returns `true` if no spelling has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // Appends a spelling that is already known to match `name`.
+    pub(crate) fn append_unchecked(
+        &mut self,
+        name: HeaderName,
+        original: String,
+    ) -> Result<(), MaxSizeReached> {
+        self.0.try_append(name, original)?;
+        Ok(())
+    }
+
+    // Forgets the spellings of `name`.
+    pub(crate) fn remove(&mut self, name: &HeaderName) {
+        self.0.remove(name);
+    }
+
+    // Returns `true` if every recorded spelling is the normalized lowercase name.
+    pub(crate) fn is_lowercase(&self) -> bool {
+        self.0
+            .iter()
+            .all(|(name, original)| name.as_str() == original)
+    }
+}
@@ -1,38 +1,65 @@
-use std::{fmt, iter};
+use std::{borrow::Cow, fmt, iter};
 
-use http::{header::GetAll, HeaderValue};
+use http::{header::GetAll, HeaderName, HeaderValue};
 use serde::{
     de,
     ser::{self, SerializeSeq},
     Deserialize, Deserializer, Serialize, Serializer,
 };
 
-use super::{header_value, insert_header_values, BorrowedNameWrapper, Either, NameWrapper};
+use super::{header_value, BorrowedNameWrapper, NameWrapper};
+use crate::{
+    cookie, duplicate_policy::InsertSeed, limits, structured_field, DuplicatePolicy,
+    RedactionPolicy,
+};
 
 type Type = http::HeaderMap;
 const EXPECT_MESSAGE: &str = "a header map";
 
-#[derive(Serialize)]
-struct BorrowedValueWrapper<'a>(#[serde(with = "crate::header_value")] &'a HeaderValue);
+struct BorrowedValueWrapper<'a>(&'a HeaderValue, bool);
 
-struct GetAllWrapper<'a>(GetAll<'a, HeaderValue>);
+impl<'a> Serialize for BorrowedValueWrapper<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        header_value::serialize_value(self.0, self.1, ser)
+    }
+}
+
+struct GetAllWrapper<'a> {
+    values: GetAll<'a, HeaderValue>,
+    with_sensitive: bool,
+    // The policy to redact the values by, and the name they are redacted under.
+    redaction: Option<(&'a RedactionPolicy, &'a HeaderName)>,
+}
+
+impl<'a> GetAllWrapper<'a> {
+    fn value(&self, value: &'a HeaderValue) -> Cow<'a, HeaderValue> {
+        match self.redaction {
+            Some((policy, name)) => policy.redact_value(name, value),
+            None => Cow::Borrowed(value),
+        }
+    }
+}
 
 impl<'a> Serialize for GetAllWrapper<'a> {
     fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
-        let mut iter = self.0.iter();
+        let mut iter = self.values.iter();
         if let Some(first) = iter.next() {
             if iter.next().is_none() {
+                let first = self.value(first);
                 if ser.is_human_readable() {
-                    return header_value::serialize(first, ser);
+                    return header_value::serialize_value(&first, self.with_sensitive, ser);
                 } else {
-                    return ser.collect_seq(iter::once(BorrowedValueWrapper(first)));
+                    return ser.collect_seq(iter::once(BorrowedValueWrapper(
+                        &first,
+                        self.with_sensitive,
+                    )));
                 }
             };
 
             let count = iter.count() + 2;
             let mut seq = ser.serialize_seq(Some(count))?;
-            for v in self.0.iter() {
-                seq.serialize_element(&BorrowedValueWrapper(v))?;
+            for v in self.values.iter() {
+                seq.serialize_element(&BorrowedValueWrapper(&self.value(v), self.with_sensitive))?;
             }
             seq.end()
         } else {
@@ -41,22 +68,164 @@ impl<'a> Serialize for GetAllWrapper<'a> {
     }
 }
 
+pub(crate) fn serialize_with_sensitive<S>(
+    headers: &Type,
+    with_sensitive: bool,
+    ser: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    ser.collect_map(headers.keys().map(|k| {
+        (
+            BorrowedNameWrapper(k),
+            GetAllWrapper {
+                values: headers.get_all(k),
+                with_sensitive,
+                redaction: None,
+            },
+        )
+    }))
+}
+
+// Writes `headers` with every value `policy` redacts replaced by its placeholder.
+pub(crate) fn serialize_redacted<S>(
+    headers: &Type,
+    policy: &RedactionPolicy,
+    ser: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    ser.collect_map(headers.keys().map(|k| {
+        (
+            BorrowedNameWrapper(k),
+            GetAllWrapper {
+                values: headers.get_all(k),
+                with_sensitive: false,
+                redaction: Some((policy, k)),
+            },
+        )
+    }))
+}
+
 pub fn serialize<S>(headers: &Type, ser: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    ser.collect_map(
-        headers
-            .keys()
-            .map(|k| (BorrowedNameWrapper(k), GetAllWrapper(headers.get_all(k)))),
-    )
+    serialize_with_sensitive(headers, false, ser)
 }
 
 #[derive(Deserialize)]
 struct ValueWrapper(#[serde(with = "crate::header_value")] HeaderValue);
 
+// The values of one header, without allocating for a single value.
+pub(crate) enum Values<T = HeaderValue> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> IntoIterator for Values<T> {
+    type Item = T;
+    type IntoIter = iter::Chain<std::option::IntoIter<T>, std::vec::IntoIter<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Values::One(value) => Some(value).into_iter().chain(Vec::new()),
+            Values::Many(values) => None.into_iter().chain(values),
+        }
+    }
+}
+
+impl<T> Values<T> {
+    fn len(&self) -> usize {
+        match self {
+            Values::One(_) => 1,
+            Values::Many(values) => values.len(),
+        }
+    }
+}
+
+struct ValueSeed<'a> {
+    name: Option<&'a HeaderName>,
+}
+
+impl<'de, 'a> de::DeserializeSeed<'de> for ValueSeed<'a> {
+    type Value = HeaderValue;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(header_value::Visitor { name: self.name })
+    }
+}
+
+// Deserializes the human-readable values of a header, either a single value or a sequence,
+// naming the header in errors when known.
+pub(crate) struct ValuesSeed<'a> {
+    pub(crate) name: Option<&'a HeaderName>,
+}
+
+impl<'a> ValuesSeed<'a> {
+    fn value(&self) -> header_value::Visitor<'a> {
+        header_value::Visitor { name: self.name }
+    }
+}
+
+impl<'de, 'a> de::Visitor<'de> for ValuesSeed<'a> {
+    type Value = Values;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(formatter, "a value or a sequence of values for header {name}"),
+            None => formatter.write_str("a header value or a sequence of header values"),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        self.value().visit_str(val).map(Values::One)
+    }
+
+    fn visit_string<E: de::Error>(self, val: String) -> Result<Self::Value, E> {
+        self.value().visit_string(val).map(Values::One)
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        self.value().visit_bytes(val).map(Values::One)
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, val: Vec<u8>) -> Result<Self::Value, E> {
+        self.value().visit_byte_buf(val).map(Values::One)
+    }
+
+    fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        self.value().visit_map(map).map(Values::One)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(limits::capacity(seq.size_hint()));
+        while let Some(value) = seq.next_element_seed(ValueSeed { name: self.name })? {
+            values.push(value);
+        }
+        Ok(Values::Many(values))
+    }
+}
+
+impl<'de, 'a> de::DeserializeSeed<'de> for ValuesSeed<'a> {
+    type Value = Values;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(self)
+    }
+}
+
 struct Visitor {
     is_human_readable: bool,
+    policy: DuplicatePolicy,
 }
 
 impl<'de> de::Visitor<'de> for Visitor {
@@ -66,46 +235,1215 @@ impl<'de> de::Visitor<'de> for Visitor {
         formatter.write_str(EXPECT_MESSAGE)
     }
 
+    fn visit_map<M>(self, access: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        let mut map = Type::with_capacity(limits::header_map_capacity(access.size_hint()));
+        visit_entries(access, &mut map, self.policy, self.is_human_readable)?;
+        Ok(map)
+    }
+}
+
+// Inserts the entries of the input into `map`.
+fn visit_entries<'de, M>(
+    mut access: M,
+    map: &mut Type,
+    policy: DuplicatePolicy,
+    is_human_readable: bool,
+) -> Result<(), M::Error>
+where
+    M: de::MapAccess<'de>,
+{
+    if is_human_readable {
+        while let Some(key) = access.next_key::<NameWrapper>()? {
+            let values = access.next_value_seed(ValuesSeed { name: Some(&key.0) })?;
+            policy.insert(map, key.0, values)?;
+        }
+    } else {
+        while let Some(key) = access.next_key::<NameWrapper>()? {
+            access.next_value_seed(InsertSeed {
+                policy,
+                map,
+                key: key.0,
+                unwrap: |v: ValueWrapper| v.0,
+            })?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn deserialize_with_policy<'de, D>(de: D, policy: DuplicatePolicy) -> Result<Type, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let is_human_readable = de.is_human_readable();
+    de.deserialize_map(Visitor {
+        is_human_readable,
+        policy,
+    })
+}
+
+pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_with_policy(de, DuplicatePolicy::default())
+}
+
+// The entries of headers in the [`header_map`](self) representation, in input order and with
+// repeated names kept apart, for a policy to insert into a map later.
+pub(crate) struct Entries(Vec<(HeaderName, Values)>);
+
+impl Entries {
+    // Inserts the entries into `map` as deserializing them with `policy` would.
+    pub(crate) fn insert_into<E: de::Error>(
+        self,
+        map: &mut Type,
+        policy: DuplicatePolicy,
+    ) -> Result<(), E> {
+        for (name, values) in self.0 {
+            policy.insert(map, name, values)?;
+        }
+        Ok(())
+    }
+}
+
+struct EntriesVisitor {
+    is_human_readable: bool,
+}
+
+impl<'de> de::Visitor<'de> for EntriesVisitor {
+    type Value = Entries;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(EXPECT_MESSAGE)
+    }
+
     fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
     where
         M: de::MapAccess<'de>,
     {
-        let mut map = Type::with_capacity(access.size_hint().unwrap_or(0));
+        let mut entries = Vec::with_capacity(limits::capacity(access.size_hint()));
+        let mut len = 0;
+        while let Some(key) = access.next_key::<NameWrapper>()? {
+            // The input alone must fit in a map, whatever it is merged into.
+            let values = if self.is_human_readable {
+                let values = access.next_value_seed(ValuesSeed { name: Some(&key.0) })?;
+                len += values.len();
+                limits::check_headers(len.saturating_sub(1))?;
+                values
+            } else {
+                access.next_value_seed(EntryValuesSeed {
+                    name: &key.0,
+                    len: &mut len,
+                })?
+            };
+            entries.push((key.0, values));
+        }
+        Ok(Entries(entries))
+    }
+}
+
+// Decodes the binary values of one entry straight into the `Vec` kept in `Entries`, counting
+// them in `len` against the limits.
+struct EntryValuesSeed<'a> {
+    name: &'a HeaderName,
+    len: &'a mut usize,
+}
+
+impl<'de, 'a> de::Visitor<'de> for EntryValuesSeed<'a> {
+    type Value = Values;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of values for header {}", self.name)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(limits::capacity(seq.size_hint()));
+        while let Some(value) = seq.next_element::<ValueWrapper>()? {
+            limits::check_headers(*self.len)?;
+            *self.len += 1;
+            values.push(value.0);
+        }
+        Ok(Values::Many(values))
+    }
+}
+
+impl<'de, 'a> de::DeserializeSeed<'de> for EntryValuesSeed<'a> {
+    type Value = Values;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_seq(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Entries {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let is_human_readable = de.is_human_readable();
+        de.deserialize_map(EntriesVisitor { is_human_readable })
+    }
+}
+
+/// A [`DeserializeSeed`](de::DeserializeSeed) that deserializes headers in the
+/// [`header_map`](self) representation into an existing [`HeaderMap`](http::HeaderMap).
+///
+/// Names already in the map count as duplicates, so the [`DuplicatePolicy`] selects whether
+/// values are [appended](DuplicatePolicy::Append), which is the default, or
+/// [replace](DuplicatePolicy::LastWins) the existing ones. Values are inserted as they are
+/// deserialized, so the map keeps the entries read before an error.
+///
+/// ```
+/// use http::{HeaderMap, HeaderValue};
+/// use http_serde_ext::{header_map::MergeInto, DuplicatePolicy};
+/// use serde::de::DeserializeSeed;
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("accept", HeaderValue::from_static("*/*"));
+/// headers.insert("x-env", HeaderValue::from_static("dev"));
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"{"x-env":"prod","x-region":"eu"}"#);
+/// MergeInto::new(&mut headers)
+///     .policy(DuplicatePolicy::LastWins)
+///     .deserialize(&mut de)
+///     .unwrap();
+/// assert_eq!(headers["accept"], "*/*");
+/// assert_eq!(headers["x-env"], "prod");
+/// assert_eq!(headers["x-region"], "eu");
+/// ```
+pub struct MergeInto<'a> {
+    headers: &'a mut Type,
+    policy: DuplicatePolicy,
+}
+
+impl<'a> MergeInto<'a> {
+    /// Creates a seed appending to `headers`.
+    pub fn new(headers: &'a mut Type) -> Self {
+        Self {
+            headers,
+            policy: DuplicatePolicy::default(),
+        }
+    }
+
+    /// Sets what happens to names that are already in the map or repeated in the input.
+    pub fn policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+struct MergeVisitor<'a> {
+    headers: &'a mut Type,
+    policy: DuplicatePolicy,
+    is_human_readable: bool,
+}
+
+impl<'de, 'a> de::Visitor<'de> for MergeVisitor<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(EXPECT_MESSAGE)
+    }
+
+    fn visit_map<M>(self, access: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        visit_entries(access, self.headers, self.policy, self.is_human_readable)
+    }
+}
+
+impl<'de, 'a> de::DeserializeSeed<'de> for MergeInto<'a> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        let is_human_readable = de.is_human_readable();
+        de.deserialize_map(MergeVisitor {
+            headers: self.headers,
+            policy: self.policy,
+            is_human_readable,
+        })
+    }
+}
+
+derive_extension_types!(super::Type);
+
+/// Serializes a [`HeaderMap`](http::HeaderMap) writing the values of every header as a
+/// sequence, even when there is only one.
+///
+/// Deserializing accepts both a single value and a sequence, as [`header_map`](super) does.
+///
+/// ```
+/// use http::{HeaderMap, HeaderValue};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::header_map::always_seq")]
+///     headers: HeaderMap,
+/// }
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("accept", HeaderValue::from_static("*/*"));
+///
+/// let json = serde_json::to_string(&MyStruct { headers }).unwrap();
+/// assert_eq!(json, r#"{"headers":{"accept":["*/*"]}}"#);
+/// ```
+pub mod always_seq {
+    use http::{header::GetAll, HeaderValue};
+    use serde::{ser::SerializeSeq, Deserializer, Serialize, Serializer};
+
+    use super::{BorrowedNameWrapper, BorrowedValueWrapper, Type};
+
+    struct ValuesWrapper<'a>(GetAll<'a, HeaderValue>);
+
+    impl<'a> Serialize for ValuesWrapper<'a> {
+        fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            let mut seq = ser.serialize_seq(Some(self.0.iter().count()))?;
+            for v in self.0.iter() {
+                seq.serialize_element(&BorrowedValueWrapper(v, false))?;
+            }
+            seq.end()
+        }
+    }
+
+    pub fn serialize<S: Serializer>(headers: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_map(
+            headers
+                .keys()
+                .map(|k| (BorrowedNameWrapper(k), ValuesWrapper(headers.get_all(k)))),
+        )
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize(de)
+    }
+
+    derive_extension_types!(super::Type);
+}
 
-        if self.is_human_readable {
-            while let Some((key, val)) = access.next_entry::<NameWrapper, Either<ValueWrapper>>()? {
-                match val {
-                    Either::One(val) => {
-                        map.insert(key.0, val.0);
+/// Same representation as [`header_map`](super), but header names are written in sorted order.
+///
+/// [`HeaderMap`](http::HeaderMap) iterates its names in an order that depends on how it was
+/// built, so equal maps can serialize differently. Sorting the names, while keeping the
+/// order of the values of each name, makes equal maps serialize to identical bytes.
+///
+/// ```
+/// use http::{HeaderMap, HeaderValue};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::header_map::sorted")]
+///     headers: HeaderMap,
+/// }
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("x-b", HeaderValue::from_static("1"));
+/// headers.insert("x-a", HeaderValue::from_static("2"));
+///
+/// let json = serde_json::to_string(&MyStruct { headers }).unwrap();
+/// assert_eq!(json, r#"{"headers":{"x-a":"2","x-b":"1"}}"#);
+/// ```
+pub mod sorted {
+    use http::HeaderName;
+    use serde::{Deserializer, Serializer};
+
+    use super::{BorrowedNameWrapper, GetAllWrapper, Type};
+
+    pub fn serialize<S: Serializer>(headers: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        let mut names: Vec<&HeaderName> = headers.keys().collect();
+        names.sort_unstable_by(|a, b| a.as_str().cmp(b.as_str()));
+        ser.collect_map(names.into_iter().map(|k| {
+            (
+                BorrowedNameWrapper(k),
+                GetAllWrapper {
+                    values: headers.get_all(k),
+                    with_sensitive: false,
+                    redaction: None,
+                },
+            )
+        }))
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize(de)
+    }
+
+    derive_extension_types!(super::Type);
+}
+
+/// Serializes a [`HeaderMap`](http::HeaderMap) as a sequence of name/value pairs.
+///
+/// Pairs are written in [`HeaderMap::iter`](http::HeaderMap::iter) order, as
+/// `{"name": ..., "value": ...}` objects like the headers of a HAR file. Values use the
+/// [`header_value`] representation. Deserializing [appends](http::HeaderMap::append) every pair.
+///
+/// ```
+/// use http::{HeaderMap, HeaderValue};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::header_map::pairs")]
+///     headers: HeaderMap,
+/// }
+///
+/// let json = r#"{"headers":[{"name":"accept","value":"text/html"},{"name":"accept","value":"*/*"}]}"#;
+/// let de: MyStruct = serde_json::from_str(json).unwrap();
+/// assert_eq!(de.headers.get_all("accept").iter().count(), 2);
+/// assert_eq!(serde_json::to_string(&de).unwrap(), json);
+/// ```
+pub mod pairs {
+    use std::{fmt, marker::PhantomData};
+
+    use http::{header::MaxSizeReached, HeaderName, HeaderValue};
+    use serde::{de, ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Type, EXPECT_MESSAGE};
+    use crate::limits;
+
+    #[derive(Serialize)]
+    struct BorrowedPair<'a> {
+        #[serde(with = "crate::header_name")]
+        name: &'a HeaderName,
+        #[serde(with = "crate::header_value")]
+        value: &'a HeaderValue,
+    }
+
+    #[derive(Deserialize)]
+    struct Pair {
+        #[serde(with = "crate::header_name")]
+        name: HeaderName,
+        #[serde(with = "crate::header_value")]
+        value: HeaderValue,
+    }
+
+    fn serialize_pairs<'a, S, I>(len: usize, pairs: I, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        I: Iterator<Item = (&'a HeaderName, &'a HeaderValue)>,
+    {
+        let mut seq = ser.serialize_seq(Some(len))?;
+        for (name, value) in pairs {
+            seq.serialize_element(&BorrowedPair { name, value })?;
+        }
+        seq.end()
+    }
+
+    pub fn serialize<S: Serializer>(headers: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        serialize_pairs(headers.len(), headers.iter(), ser)
+    }
+
+    // Deserializes the pairs into `T`, calling `append` for each.
+    struct Visitor<T, F> {
+        append: F,
+        ph: PhantomData<T>,
+    }
+
+    impl<'de, T, F> de::Visitor<'de> for Visitor<T, F>
+    where
+        T: Default,
+        F: Fn(&mut T, HeaderName, HeaderValue) -> Result<(), MaxSizeReached>,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str(EXPECT_MESSAGE)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::SeqAccess<'de>,
+        {
+            let mut val = T::default();
+            let mut len = 0;
+            while let Some(pair) = seq.next_element::<Pair>()? {
+                limits::check_headers(len)?;
+                len += 1;
+                (self.append)(&mut val, pair.name, pair.value)
+                    .map_err(limits::header_map_full)?;
+            }
+            Ok(val)
+        }
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        de.deserialize_seq(Visitor {
+            append: |map: &mut Type, name, value| {
+                map.try_append(name, value)?;
+                Ok(())
+            },
+            ph: PhantomData,
+        })
+    }
+
+    derive_extension_types!(super::Type);
+
+    /// Serializes a [`HeaderMap`](http::HeaderMap) as a sequence of name/value pairs in the
+    /// arrival order recorded by a [`HeaderOrder`](crate::HeaderOrder).
+    ///
+    /// Values the order doesn't account for are written after the others, in
+    /// [`HeaderMap::iter`](http::HeaderMap::iter) order. Deserializing appends every pair
+    /// and records its name in the [`HeaderOrder`](crate::HeaderOrder).
+    ///
+    /// ```
+    /// use http::{HeaderMap, HeaderValue};
+    /// use http_serde_ext::HeaderOrder;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyStruct {
+    ///     #[serde(with = "http_serde_ext::header_map::pairs::with_order")]
+    ///     headers: (HeaderMap, HeaderOrder),
+    /// }
+    ///
+    /// let json = r#"{"headers":[{"name":"x-a","value":"1"},{"name":"host","value":"example.com"},{"name":"x-a","value":"2"}]}"#;
+    /// let de: MyStruct = serde_json::from_str(json).unwrap();
+    /// assert_eq!(de.headers.1.len(), 3);
+    /// assert_eq!(serde_json::to_string(&de).unwrap(), json);
+    /// ```
+    pub mod with_order {
+        use std::marker::PhantomData;
+
+        use http::{header::ValueIter, HeaderMap, HeaderName, HeaderValue};
+        use serde::{Deserializer, Serializer};
+
+        use super::{serialize_pairs, Visitor};
+        use crate::HeaderOrder;
+
+        type Type = (HeaderMap, HeaderOrder);
+
+        pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
+            let (headers, order) = val;
+            let mut remaining: Vec<(&HeaderName, ValueIter<HeaderValue>)> = headers
+                .keys()
+                .map(|name| (name, headers.get_all(name).iter()))
+                .collect();
+
+            let mut pairs = Vec::with_capacity(headers.len());
+            for name in order.iter() {
+                let value = remaining
+                    .iter_mut()
+                    .find(|(n, _)| *n == name)
+                    .and_then(|(_, values)| values.next());
+                if let Some(value) = value {
+                    pairs.push((name, value));
+                }
+            }
+            for (name, values) in remaining {
+                pairs.extend(values.map(|value| (name, value)));
+            }
+            serialize_pairs(pairs.len(), pairs.into_iter(), ser)
+        }
+
+        pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            de.deserialize_seq(Visitor {
+                append: |val: &mut Type, name: HeaderName, value| {
+                    val.0.try_append(name.clone(), value)?;
+                    val.1.push(name);
+                    Ok(())
+                },
+                ph: PhantomData,
+            })
+        }
+
+        derive_extension_types!(super::Type);
+    }
+}
+
+// Defines `error`, `append`, `last_wins` and `first_wins` modules for a representation,
+// deserializing with the matching policy.
+macro_rules! duplicate_policy_mods {
+    ($ty:ty, $serialize:path, $deserialize_with_policy:path) => {
+        duplicate_policy_mods!(
+            @mod $ty, $serialize, $deserialize_with_policy,
+            error, Error, "Fails to deserialize a header name that appears more than once."
+        );
+        duplicate_policy_mods!(
+            @mod $ty, $serialize, $deserialize_with_policy,
+            append, Append, "Appends the values of a repeated header name."
+        );
+        duplicate_policy_mods!(
+            @mod $ty, $serialize, $deserialize_with_policy,
+            last_wins, LastWins, "Keeps the last values of a repeated header name."
+        );
+        duplicate_policy_mods!(
+            @mod $ty, $serialize, $deserialize_with_policy,
+            first_wins, FirstWins, "Keeps the first values of a repeated header name."
+        );
+    };
+    (@mod $ty:ty, $serialize:path, $deserialize_with_policy:path, $name:ident, $policy:ident, $doc:literal) => {
+        #[doc = $doc]
+        pub mod $name {
+            use serde::{Deserializer, Serializer};
+
+            use crate::DuplicatePolicy;
+
+            pub fn serialize<S: Serializer>(headers: &$ty, ser: S) -> Result<S::Ok, S::Error> {
+                $serialize(headers, ser)
+            }
+
+            pub fn deserialize<'de, D>(de: D) -> Result<$ty, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                $deserialize_with_policy(de, DuplicatePolicy::$policy)
+            }
+
+            derive_extension_types!($ty);
+        }
+    };
+}
+
+/// Same representation as [`header_map`](super), deserialized with another
+/// [`DuplicatePolicy`] for header names that appear more than once.
+pub mod duplicate {
+    duplicate_policy_mods!(
+        http::HeaderMap,
+        crate::header_map::serialize,
+        crate::header_map::deserialize_with_policy
+    );
+}
+
+/// Serializes a [`HeaderMap`](http::HeaderMap) keeping the
+/// [sensitivity](http::HeaderValue::is_sensitive) of its values in human-readable formats.
+///
+/// Sensitive values are written as by
+/// [`header_value::with_sensitive`]. Binary formats always
+/// keep the flag, and deserializing accepts the same shapes as [`header_map`](super).
+///
+/// ```
+/// use http::{header::AUTHORIZATION, HeaderMap, HeaderValue};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::header_map::with_sensitive")]
+///     headers: HeaderMap,
+/// }
+///
+/// let json = r#"{"headers":{"authorization":{"sensitive":true,"value":"Basic Zm9vOmJhcg=="}}}"#;
+/// let de: MyStruct = serde_json::from_str(json).unwrap();
+/// assert!(de.headers[AUTHORIZATION].is_sensitive());
+/// assert_eq!(serde_json::to_string(&de).unwrap(), json);
+/// ```
+pub mod with_sensitive {
+    use serde::{Deserializer, Serializer};
+
+    use super::Type;
+
+    pub fn serialize<S: Serializer>(headers: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        super::serialize_with_sensitive(headers, true, ser)
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize(de)
+    }
+
+    derive_extension_types!(super::Type);
+}
+
+/// Serializes a [`HeaderMap`](http::HeaderMap) with its values redacted by the default
+/// [`RedactionPolicy`].
+///
+/// Deserializing fails if a value is a redaction placeholder, so redacted headers can't be
+/// replayed by accident.
+///
+/// ```
+/// use http::{HeaderMap, HeaderValue};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::header_map::redacted")]
+///     headers: HeaderMap,
+/// }
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("cookie", HeaderValue::from_static("session=secret"));
+/// headers.insert("accept", HeaderValue::from_static("*/*"));
+///
+/// let json = serde_json::to_string(&MyStruct { headers }).unwrap();
+/// assert_eq!(json, r#"{"headers":{"cookie":"<redacted>","accept":"*/*"}}"#);
+/// assert!(serde_json::from_str::<MyStruct>(&json).is_err());
+/// ```
+pub mod redacted {
+    use serde::{de, Deserializer, Serializer};
+
+    use super::Type;
+    use crate::{redaction::DEFAULT_POLICY, RedactionPolicy};
+
+    pub fn serialize<S: Serializer>(headers: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        super::serialize_redacted(headers, &DEFAULT_POLICY, ser)
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let headers = super::deserialize(de)?;
+        for (name, value) in &headers {
+            if RedactionPolicy::is_placeholder(value) {
+                return Err(de::Error::custom(format!("header {name} is redacted")));
+            }
+        }
+        Ok(headers)
+    }
+
+    derive_extension_types!(super::Type);
+}
+
+/// [`Serialize`]/[`Deserialize`] for a [`HeaderMap`](http::HeaderMap) together with the
+/// original spelling of its names in a [`HeaderCaseMap`](crate::HeaderCaseMap).
+///
+/// Names are written as originally spelled, falling back to the lowercase name for
+/// occurrences without a recorded spelling. Values of a name that was spelled differently
+/// are grouped under each spelling, so their relative order is only kept within a spelling.
+/// Deserializing appends every entry to the [`HeaderMap`](http::HeaderMap) and records its
+/// spelling. The [`duplicate`] submodules select another [`DuplicatePolicy`], under which a
+/// name written with two spellings is a duplicate.
+///
+/// ```
+/// use http::{HeaderMap, HeaderValue};
+/// use http_serde_ext::HeaderCaseMap;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::header_map::original_case")]
+///     headers: (HeaderMap, HeaderCaseMap),
+/// }
+///
+/// let json = r#"{"headers":{"Content-Type":"text/html","X-Foo":["a","b"]}}"#;
+/// let de: MyStruct = serde_json::from_str(json).unwrap();
+/// assert_eq!(de.headers.0["content-type"], HeaderValue::from_static("text/html"));
+/// assert_eq!(serde_json::to_string(&de).unwrap(), json);
+/// ```
+pub mod original_case {
+    use std::fmt;
+
+    use http::{HeaderMap, HeaderName, HeaderValue};
+    use serde::{de, Deserializer, Serialize, Serializer};
+
+    use super::{
+        header_value, BorrowedValueWrapper, ValueWrapper, ValuesSeed, EXPECT_MESSAGE,
+    };
+    use crate::{limits, DuplicatePolicy, HeaderCaseMap};
+
+    type Type = (HeaderMap, HeaderCaseMap);
+
+    struct ValuesWrapper<'a>(Vec<&'a HeaderValue>);
+
+    impl<'a> Serialize for ValuesWrapper<'a> {
+        fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            match self.0.as_slice() {
+                [value] if ser.is_human_readable() => header_value::serialize(value, ser),
+                values => ser.collect_seq(values.iter().map(|v| BorrowedValueWrapper(v, false))),
+            }
+        }
+    }
+
+    pub(crate) fn serialize_with_case<S>(
+        headers: &HeaderMap,
+        case: &HeaderCaseMap,
+        ser: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut groups: Vec<(&str, ValuesWrapper)> = Vec::with_capacity(headers.keys_len());
+        for name in headers.keys() {
+            let start = groups.len();
+            let mut spellings = case.get_all(name);
+            for value in headers.get_all(name) {
+                let spelling = spellings.next().unwrap_or(name.as_str());
+                match groups[start..].iter_mut().find(|(s, _)| *s == spelling) {
+                    Some((_, values)) => values.0.push(value),
+                    None => groups.push((spelling, ValuesWrapper(vec![value]))),
+                }
+            }
+        }
+        ser.collect_map(groups)
+    }
+
+    pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        serialize_with_case(&val.0, &val.1, ser)
+    }
+
+    struct Visitor {
+        is_human_readable: bool,
+        policy: DuplicatePolicy,
+    }
+
+    impl Visitor {
+        fn name<E: de::Error>(key: &str) -> Result<HeaderName, E> {
+            HeaderName::from_bytes(key.as_bytes()).map_err(de::Error::custom)
+        }
+
+        // Inserts the values of one entry of the input as the policy says, recording the
+        // spelling of each value kept. Values are taken from `next` as they are decoded, and
+        // all of them are consumed, even those the policy ignores.
+        fn insert_with<E: de::Error>(
+            &self,
+            map: &mut Type,
+            name: HeaderName,
+            key: String,
+            mut next: impl FnMut() -> Result<Option<HeaderValue>, E>,
+        ) -> Result<(), E> {
+            let Some(mut value) = next()? else {
+                return Err(de::Error::custom(format!("no value for header {key}")));
+            };
+            let mut keep = true;
+            if map.0.contains_key(&name) {
+                match self.policy {
+                    DuplicatePolicy::Error => {
+                        return Err(de::Error::custom(format!("duplicate header {name}")));
                     }
-                    Either::Many(values) => {
-                        insert_header_values::<M, HeaderValue>(
-                            &mut map,
-                            key.0,
-                            values.into_iter().map(|v| v.0),
-                        )?;
+                    DuplicatePolicy::Append => {}
+                    DuplicatePolicy::LastWins => {
+                        map.0.remove(&name);
+                        map.1.remove(&name);
                     }
-                };
+                    DuplicatePolicy::FirstWins => keep = false,
+                }
             }
-        } else {
-            while let Some((key, values)) = access.next_entry::<NameWrapper, Vec<ValueWrapper>>()? {
-                insert_header_values::<M, HeaderValue>(
-                    &mut map,
-                    key.0,
-                    values.into_iter().map(|v| v.0),
-                )?;
+            loop {
+                if keep {
+                    limits::check_headers(map.0.len())?;
+                    map.0
+                        .try_append(name.clone(), value)
+                        .map_err(limits::header_map_full)?;
+                    map.1
+                        .append_unchecked(name.clone(), key.clone())
+                        .map_err(limits::header_map_full)?;
+                }
+                match next()? {
+                    Some(next) => value = next,
+                    None => return Ok(()),
+                }
             }
         }
+    }
+
+    // Decodes the binary values of one entry and inserts them one by one.
+    struct InsertSeed<'a> {
+        visitor: &'a Visitor,
+        map: &'a mut Type,
+        name: HeaderName,
+        key: String,
+    }
+
+    impl<'de, 'a> de::Visitor<'de> for InsertSeed<'a> {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a sequence of values for header {}", self.key)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::SeqAccess<'de>,
+        {
+            self.visitor.insert_with(self.map, self.name, self.key, || {
+                Ok(seq.next_element::<ValueWrapper>()?.map(|v| v.0))
+            })
+        }
+    }
+
+    impl<'de, 'a> de::DeserializeSeed<'de> for InsertSeed<'a> {
+        type Value = ();
+
+        fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+            de.deserialize_seq(self)
+        }
+    }
+
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = Type;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str(EXPECT_MESSAGE)
+        }
+
+        fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+        where
+            M: de::MapAccess<'de>,
+        {
+            let mut map = (
+                HeaderMap::with_capacity(limits::header_map_capacity(access.size_hint())),
+                HeaderCaseMap::new(),
+            );
+
+            if self.is_human_readable {
+                while let Some(key) = access.next_key::<String>()? {
+                    let name = Self::name(&key)?;
+                    let mut values = access
+                        .next_value_seed(ValuesSeed { name: Some(&name) })?
+                        .into_iter();
+                    self.insert_with(&mut map, name, key, || Ok(values.next()))?;
+                }
+            } else {
+                while let Some(key) = access.next_key::<String>()? {
+                    let name = Self::name(&key)?;
+                    access.next_value_seed(InsertSeed {
+                        visitor: &self,
+                        map: &mut map,
+                        name,
+                        key,
+                    })?;
+                }
+            }
+            Ok(map)
+        }
+    }
+
+    fn deserialize_with_policy<'de, D>(de: D, policy: DuplicatePolicy) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let is_human_readable = de.is_human_readable();
+        de.deserialize_map(Visitor {
+            is_human_readable,
+            policy,
+        })
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_with_policy(de, DuplicatePolicy::default())
+    }
+
+    derive_extension_types!(super::Type);
+
+    /// Same representation as [`original_case`](super), deserialized with another
+    /// [`DuplicatePolicy`] for header names that appear more than once.
+    pub mod duplicate {
+        duplicate_policy_mods!(
+            (http::HeaderMap, crate::HeaderCaseMap),
+            crate::header_map::original_case::serialize,
+            crate::header_map::original_case::deserialize_with_policy
+        );
+    }
+}
+
+// Human-readable representations that write the values of some headers differently.
+#[derive(Clone, Copy)]
+enum Mode<'a> {
+    // Structured fields, for the known headers and those listed.
+    Structured(&'a [(HeaderName, structured_field::Kind)]),
+    Cookies,
+}
+
+struct ModeValuesWrapper<'a> {
+    headers: &'a Type,
+    name: &'a HeaderName,
+    mode: Mode<'a>,
+}
+
+impl<'a> Serialize for ModeValuesWrapper<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let values = self.headers.get_all(self.name);
+        match self.mode {
+            Mode::Structured(known) => {
+                if let Some(kind) = structured_field::Kind::of_known(self.name, known) {
+                    let values: Vec<&HeaderValue> = values.iter().collect();
+                    return structured_field::serialize_values(kind, &values, ser);
+                }
+            }
+            Mode::Cookies => {
+                if let Some(kind) = cookie::Kind::of(self.name) {
+                    let values: Vec<&HeaderValue> = values.iter().collect();
+                    return cookie::serialize_values(kind, &values, ser);
+                }
+            }
+        }
+        GetAllWrapper {
+            values,
+            with_sensitive: false,
+            redaction: None,
+        }
+        .serialize(ser)
+    }
+}
+
+fn serialize_with_mode<S>(headers: &Type, mode: Mode<'_>, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if !ser.is_human_readable() {
+        return serialize(headers, ser);
+    }
+    ser.collect_map(headers.keys().map(|name| {
+        (
+            BorrowedNameWrapper(name),
+            ModeValuesWrapper {
+                headers,
+                name,
+                mode,
+            },
+        )
+    }))
+}
+
+struct ModeVisitor<'a> {
+    mode: Mode<'a>,
+    policy: DuplicatePolicy,
+}
+
+impl<'de, 'a> de::Visitor<'de> for ModeVisitor<'a> {
+    type Value = Type;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(EXPECT_MESSAGE)
+    }
+
+    fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        let mut map = Type::with_capacity(limits::header_map_capacity(access.size_hint()));
+
+        while let Some(key) = access.next_key::<NameWrapper>()? {
+            let values = match self.mode {
+                Mode::Structured(known) => structured_field::Kind::of_known(&key.0, known)
+                    .map(|kind| access.next_value_seed(structured_field::FieldSeed {
+                            kind,
+                            name: Some(&key.0),
+                        })),
+                Mode::Cookies => cookie::Kind::of(&key.0)
+                    .map(|kind| access.next_value_seed(cookie::ValuesSeed { kind })),
+            };
+            let values = match values {
+                Some(values) => Values::Many(values?),
+                None => access.next_value_seed(ValuesSeed { name: Some(&key.0) })?,
+            };
+            self.policy.insert(&mut map, key.0, values)?;
+        }
         Ok(map)
     }
 }
 
-pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+fn deserialize_with_mode<'de, D>(
+    de: D,
+    mode: Mode<'_>,
+    policy: DuplicatePolicy,
+) -> Result<Type, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let is_human_readable = de.is_human_readable();
-    de.deserialize_map(Visitor { is_human_readable })
+    if de.is_human_readable() {
+        de.deserialize_map(ModeVisitor { mode, policy })
+    } else {
+        deserialize_with_policy(de, policy)
+    }
 }
 
-derive_extension_types!(super::Type);
+/// Serializes a [`HeaderMap`](http::HeaderMap) writing known structured headers as RFC 8941
+/// structured fields in human-readable formats.
+///
+/// Values of the known headers are written as by [`header_value::structured`]. Those spread
+/// over several lines, or that don't parse, are written as `{"$raw": ...}` with the
+/// [`header_map`](super) representation of their values. Other headers are written as by
+/// [`header_map`](super), and binary formats use the [`header_map`](super) representation.
+/// The [`duplicate`] submodules select another [`DuplicatePolicy`].
+///
+/// The known headers are:
+/// - lists: `accept-ch`, `cache-status`, `client-cert-chain`, `proxy-status`, `sec-ch-ua`,
+///   `sec-ch-ua-full-version-list`
+/// - dictionaries: `cdn-cache-control`, `content-digest`, `priority`, `repr-digest`,
+///   `signature`, `signature-input`, `want-content-digest`, `want-repr-digest`
+/// - items: `client-cert`, `sec-ch-prefers-color-scheme`, `sec-ch-prefers-reduced-motion`,
+///   `sec-ch-ua-arch`, `sec-ch-ua-bitness`, `sec-ch-ua-full-version`, `sec-ch-ua-mobile`,
+///   `sec-ch-ua-model`, `sec-ch-ua-platform`, `sec-ch-ua-platform-version`, `sec-ch-ua-wow64`
+///
+/// [`serialize_with_known`](structured::serialize_with_known) and
+/// [`deserialize_with_known`](structured::deserialize_with_known) add other headers to the list.
+///
+/// ```
+/// use http::HeaderMap;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::header_map::structured")]
+///     headers: HeaderMap,
+/// }
+///
+/// let json = r#"{"headers":{"sec-ch-ua-mobile":false,"sec-ch-ua-platform":"Linux","accept":"*/*"}}"#;
+/// let de: MyStruct = serde_json::from_str(json).unwrap();
+/// assert_eq!(de.headers["sec-ch-ua-mobile"], "?0");
+/// assert_eq!(de.headers["sec-ch-ua-platform"], "\"Linux\"");
+/// assert_eq!(serde_json::to_string(&de).unwrap(), json);
+/// ```
+pub mod structured {
+    use http::HeaderName;
+    use serde::{Deserializer, Serializer};
+
+    use super::{Mode, Type};
+    use crate::{DuplicatePolicy, StructuredKind};
+
+    pub fn serialize<S: Serializer>(headers: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        serialize_with_known(headers, &[], ser)
+    }
+
+    /// Serializes as [`serialize`] does, also writing the `known` headers as structured
+    /// fields of the given kind. They take precedence over the headers known to the crate.
+    ///
+    /// ```
+    /// use http::{header::HeaderName, HeaderMap, HeaderValue};
+    /// use http_serde_ext::StructuredKind;
+    /// use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    ///
+    /// fn known() -> [(HeaderName, StructuredKind); 1] {
+    ///     [(HeaderName::from_static("x-flags"), StructuredKind::List)]
+    /// }
+    ///
+    /// fn serialize<S: Serializer>(headers: &HeaderMap, ser: S) -> Result<S::Ok, S::Error> {
+    ///     http_serde_ext::header_map::structured::serialize_with_known(headers, &known(), ser)
+    /// }
+    ///
+    /// fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<HeaderMap, D::Error> {
+    ///     http_serde_ext::header_map::structured::deserialize_with_known(de, &known())
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyStruct {
+    ///     #[serde(serialize_with = "serialize", deserialize_with = "deserialize")]
+    ///     headers: HeaderMap,
+    /// }
+    ///
+    /// let json = r#"{"headers":{"x-flags":[{"token":"a"},1]}}"#;
+    /// let de: MyStruct = serde_json::from_str(json).unwrap();
+    /// assert_eq!(de.headers["x-flags"], "a, 1");
+    /// assert_eq!(serde_json::to_string(&de).unwrap(), json);
+    /// ```
+    pub fn serialize_with_known<S: Serializer>(
+        headers: &Type,
+        known: &[(HeaderName, StructuredKind)],
+        ser: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::serialize_with_mode(headers, Mode::Structured(known), ser)
+    }
+
+    fn deserialize_with_policy<'de, D>(de: D, policy: DuplicatePolicy) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize_with_mode(de, Mode::Structured(&[]), policy)
+    }
+
+    /// Deserializes as [`deserialize`] does, also reading the `known` headers as structured
+    /// fields of the given kind. See [`serialize_with_known`].
+    pub fn deserialize_with_known<'de, D>(
+        de: D,
+        known: &[(HeaderName, StructuredKind)],
+    ) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize_with_mode(de, Mode::Structured(known), DuplicatePolicy::default())
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_with_policy(de, DuplicatePolicy::default())
+    }
+
+    derive_extension_types!(super::Type);
+
+    /// Same representation as [`structured`](super), deserialized with another
+    /// [`DuplicatePolicy`] for header names that appear more than once.
+    pub mod duplicate {
+        duplicate_policy_mods!(
+            http::HeaderMap,
+            crate::header_map::structured::serialize,
+            crate::header_map::structured::deserialize_with_policy
+        );
+    }
+}
+
+/// Serializes a [`HeaderMap`](http::HeaderMap) writing `Cookie` and `Set-Cookie` values as
+/// objects in human-readable formats.
+///
+/// A `Cookie` value is written as a sequence of `{"name": ..., "value": ...}` objects, and a
+/// `Set-Cookie` value as one such object with its `attributes` in order, `true` standing for
+/// attributes without a value. Each `Set-Cookie` value stays a separate element, and several
+/// `Cookie` values are written as a sequence of sequences. Values are only written this way
+/// when they are rebuilt byte for byte on deserialize, and as by [`header_map`](super)
+/// otherwise. Binary formats use the [`header_map`](super) representation. The
+/// [`duplicate`] submodules select another [`DuplicatePolicy`].
+///
+/// ```
+/// use http::HeaderMap;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::header_map::cookies")]
+///     headers: HeaderMap,
+/// }
+///
+/// let json = r#"{"headers":{"set-cookie":{"name":"id","value":"a3fWa","attributes":{"Max-Age":"2592000","HttpOnly":true}}}}"#;
+/// let de: MyStruct = serde_json::from_str(json).unwrap();
+/// assert_eq!(de.headers["set-cookie"], "id=a3fWa; Max-Age=2592000; HttpOnly");
+/// assert_eq!(serde_json::to_string(&de).unwrap(), json);
+/// ```
+pub mod cookies {
+    use serde::{Deserializer, Serializer};
+
+    use super::{Mode, Type};
+    use crate::DuplicatePolicy;
+
+    pub fn serialize<S: Serializer>(headers: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        super::serialize_with_mode(headers, Mode::Cookies, ser)
+    }
+
+    fn deserialize_with_policy<'de, D>(de: D, policy: DuplicatePolicy) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize_with_mode(de, Mode::Cookies, policy)
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_with_policy(de, DuplicatePolicy::default())
+    }
+
+    derive_extension_types!(super::Type);
+
+    /// Same representation as [`cookies`](super), deserialized with another
+    /// [`DuplicatePolicy`] for header names that appear more than once.
+    pub mod duplicate {
+        duplicate_policy_mods!(
+            http::HeaderMap,
+            crate::header_map::cookies::serialize,
+            crate::header_map::cookies::deserialize_with_policy
+        );
+    }
+}
@@ -1,13 +1,14 @@
 use std::{fmt, iter, marker::PhantomData};
 
-use http::header::GetAll;
+use http::{header::GetAll, HeaderName};
 use serde::{
-    de,
+    de::{self, value, IntoDeserializer},
     ser::{self, SerializeSeq},
     Deserialize, Deserializer, Serialize, Serializer,
 };
 
-use super::{insert_header_values, BorrowedNameWrapper, Either, NameWrapper};
+use super::{BorrowedNameWrapper, NameWrapper};
+use crate::{duplicate_policy::InsertSeed, header_map::Values, limits, DuplicatePolicy};
 
 type Type<T> = http::HeaderMap<T>;
 const EXPECT_MESSAGE: &str = "a header map";
@@ -50,11 +51,157 @@ where
     )
 }
 
+// Deserializes one human-readable value, naming the header in errors.
+struct ValueSeed<'a, T> {
+    name: &'a HeaderName,
+    ph: PhantomData<T>,
+}
+
+impl<'a, T> ValueSeed<'a, T> {
+    fn error<E: de::Error>(&self, err: impl fmt::Display) -> E {
+        E::custom(format_args!("invalid value for header {}: {err}", self.name))
+    }
+}
+
+impl<'de, 'a, T> de::DeserializeSeed<'de> for ValueSeed<'a, T>
+where
+    T: for<'b> Deserialize<'b>,
+{
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        T::deserialize(de).map_err(|err| self.error(err))
+    }
+}
+
+// Deserializes the human-readable values of a header, either a single value or a sequence,
+// naming the header in errors. A sequence always holds several values.
+struct ValuesSeed<'a, T> {
+    name: &'a HeaderName,
+    ph: PhantomData<T>,
+}
+
+impl<'a, T> ValuesSeed<'a, T>
+where
+    T: for<'b> Deserialize<'b>,
+{
+    fn value(&self) -> ValueSeed<'a, T> {
+        ValueSeed {
+            name: self.name,
+            ph: PhantomData,
+        }
+    }
+
+    fn one<'de, D: Deserializer<'de>>(&self, de: D) -> Result<Values<T>, D::Error> {
+        de::DeserializeSeed::deserialize(self.value(), de).map(Values::One)
+    }
+}
+
+impl<'de, 'a, T> de::Visitor<'de> for ValuesSeed<'a, T>
+where
+    T: for<'b> Deserialize<'b>,
+{
+    type Value = Values<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a value or a sequence of values for header {}",
+            self.name
+        )
+    }
+
+    fn visit_bool<E: de::Error>(self, val: bool) -> Result<Self::Value, E> {
+        self.one(val.into_deserializer())
+    }
+
+    fn visit_i64<E: de::Error>(self, val: i64) -> Result<Self::Value, E> {
+        self.one(val.into_deserializer())
+    }
+
+    fn visit_u64<E: de::Error>(self, val: u64) -> Result<Self::Value, E> {
+        self.one(val.into_deserializer())
+    }
+
+    fn visit_f64<E: de::Error>(self, val: f64) -> Result<Self::Value, E> {
+        self.one(val.into_deserializer())
+    }
+
+    fn visit_char<E: de::Error>(self, val: char) -> Result<Self::Value, E> {
+        self.one(val.into_deserializer())
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        self.one(val.into_deserializer())
+    }
+
+    fn visit_string<E: de::Error>(self, val: String) -> Result<Self::Value, E> {
+        self.one(val.into_deserializer())
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        self.one(value::BytesDeserializer::new(val))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.one(().into_deserializer())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.one(().into_deserializer())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.one(de)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.one(de)
+    }
+
+    fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        self.one(value::MapAccessDeserializer::new(map))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        self.one(value::EnumAccessDeserializer::new(data))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(limits::capacity(seq.size_hint()));
+        while let Some(value) = seq.next_element_seed(self.value())? {
+            values.push(value);
+        }
+        Ok(Values::Many(values))
+    }
+}
+
+impl<'de, 'a, T> de::DeserializeSeed<'de> for ValuesSeed<'a, T>
+where
+    T: for<'b> Deserialize<'b>,
+{
+    type Value = Values<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(self)
+    }
+}
+
 struct Visitor<T>
 where
     T: for<'a> Deserialize<'a>,
 {
     is_human_readable: bool,
+    policy: DuplicatePolicy,
     _ph: PhantomData<T>,
 }
 
@@ -72,22 +219,24 @@ where
     where
         M: de::MapAccess<'de>,
     {
-        let mut map = Type::<T>::with_capacity(access.size_hint().unwrap_or_default());
+        let mut map = Type::<T>::with_capacity(limits::header_map_capacity(access.size_hint()));
 
         if self.is_human_readable {
-            while let Some((key, val)) = access.next_entry::<NameWrapper, Either<T>>()? {
-                match val {
-                    Either::One(val) => {
-                        map.insert(key.0, val);
-                    }
-                    Either::Many(values) => {
-                        insert_header_values::<M, T>(&mut map, key.0, values.into_iter())?;
-                    }
-                };
+            while let Some(key) = access.next_key::<NameWrapper>()? {
+                let values = access.next_value_seed(ValuesSeed {
+                    name: &key.0,
+                    ph: PhantomData,
+                })?;
+                self.policy.insert(&mut map, key.0, values)?;
             }
         } else {
-            while let Some((key, values)) = access.next_entry::<NameWrapper, Vec<T>>()? {
-                insert_header_values::<M, T>(&mut map, key.0, values.into_iter())?;
+            while let Some(key) = access.next_key::<NameWrapper>()? {
+                access.next_value_seed(InsertSeed {
+                    policy: self.policy,
+                    map: &mut map,
+                    key: key.0,
+                    unwrap: |v: T| v,
+                })?;
             }
         }
 
@@ -95,7 +244,10 @@ where
     }
 }
 
-pub fn deserialize<'de, D, T>(de: D) -> Result<Type<T>, D::Error>
+pub(crate) fn deserialize_with_policy<'de, D, T>(
+    de: D,
+    policy: DuplicatePolicy,
+) -> Result<Type<T>, D::Error>
 where
     D: Deserializer<'de>,
     T: for<'a> Deserialize<'a>,
@@ -103,8 +255,102 @@ where
     let is_human_readable = de.is_human_readable();
     de.deserialize_map(Visitor::<T> {
         is_human_readable,
+        policy,
         _ph: PhantomData,
     })
 }
 
+pub fn deserialize<'de, D, T>(de: D) -> Result<Type<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: for<'a> Deserialize<'a>,
+{
+    deserialize_with_policy(de, DuplicatePolicy::default())
+}
+
 derive_extension_types!(super::Type<T>, T);
+
+/// Serializes a [`HeaderMap<T>`](http::HeaderMap) writing the values of every header as a
+/// sequence, even when there is only one.
+///
+/// Deserializing accepts both a single value and a sequence, as
+/// [`header_map_generic`](super) does.
+pub mod always_seq {
+    use http::header::GetAll;
+    use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{BorrowedNameWrapper, Type};
+
+    struct ValuesWrapper<'a, T: Serialize>(GetAll<'a, T>);
+
+    impl<'a, T: Serialize> Serialize for ValuesWrapper<'a, T> {
+        fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            let mut seq = ser.serialize_seq(Some(self.0.iter().count()))?;
+            for v in self.0.iter() {
+                seq.serialize_element(v)?;
+            }
+            seq.end()
+        }
+    }
+
+    pub fn serialize<S, T>(headers: &Type<T>, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        ser.collect_map(
+            headers
+                .keys()
+                .map(|k| (BorrowedNameWrapper(k), ValuesWrapper(headers.get_all(k)))),
+        )
+    }
+
+    pub fn deserialize<'de, D, T>(de: D) -> Result<Type<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: for<'a> Deserialize<'a>,
+    {
+        super::deserialize(de)
+    }
+
+    derive_extension_types!(super::Type<T>, T);
+}
+
+/// Same representation as [`header_map_generic`](super), deserialized with another
+/// [`DuplicatePolicy`] for header names that appear more than once.
+pub mod duplicate {
+    macro_rules! policy_mod {
+        ($name:ident, $policy:ident, $doc:literal) => {
+            #[doc = $doc]
+            pub mod $name {
+                use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+                use super::super::Type;
+                use crate::DuplicatePolicy;
+
+                pub fn serialize<S, T>(headers: &Type<T>, ser: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                    T: Serialize,
+                {
+                    super::super::serialize(headers, ser)
+                }
+
+                pub fn deserialize<'de, D, T>(de: D) -> Result<Type<T>, D::Error>
+                where
+                    D: Deserializer<'de>,
+                    T: for<'a> Deserialize<'a>,
+                {
+                    DuplicatePolicy::$policy.deserialize_header_map_generic(de)
+                }
+
+                derive_extension_types!(super::Type<T>, T);
+            }
+        };
+    }
+
+    policy_mod!(error, Error, "Fails to deserialize a header name that appears more than once.");
+    policy_mod!(append, Append, "Appends the values of a repeated header name.");
+    policy_mod!(last_wins, LastWins, "Keeps the last values of a repeated header name.");
+    policy_mod!(first_wins, FirstWins, "Keeps the first values of a repeated header name.");
+}
@@ -0,0 +1,58 @@
+use http::{header::InvalidHeaderName, HeaderName};
+
+/// The order header lines were received in.
+///
+/// A [`HeaderMap`](http::HeaderMap) groups the values of a name together, so the interleaving
+/// of different names on the wire is lost. This records the name of every line in arrival
+/// order: the n-th occurrence of a name stands for the n-th value of that name in the map.
+/// [`header_map::pairs::with_order`](crate::header_map::pairs::with_order) writes the values
+/// in this order and fills it in again when deserializing.
+///
+/// hyper keeps its own header order private, so this type has to be filled in by whoever
+/// parses the message, e.g. from the raw header lines.
+///
+/// ```
+/// use http::header::{ACCEPT, HOST};
+/// use http_serde_ext::HeaderOrder;
+///
+/// let mut order = HeaderOrder::new();
+/// order.append("Accept").unwrap();
+/// order.append("Host").unwrap();
+/// order.append("accept").unwrap();
+/// assert_eq!(order.iter().collect::<Vec<_>>(), [&ACCEPT, &HOST, &ACCEPT]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeaderOrder(Vec<HeaderName>);
+
+impl HeaderOrder {
+    /// Creates an empty order.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records another header line named `name`.
+    pub fn append(&mut self, name: &str) -> Result<(), InvalidHeaderName> {
+        self.0.push(HeaderName::from_bytes(name.as_bytes())?);
+        Ok(())
+    }
+
+    /// Returns the names of the recorded lines, in arrival order.
+    pub fn iter(&self) -> impl Iterator<Item = &HeaderName> {
+        self.0.iter()
+    }
+
+    /// Returns the number of recorded lines.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no line has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // Records a line whose name is already parsed.
+    pub(crate) fn push(&mut self, name: HeaderName) {
+        self.0.push(name);
+    }
+}
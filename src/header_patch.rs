@@ -0,0 +1,347 @@
+use std::fmt;
+
+use http::{header::Entry, HeaderMap, HeaderName, HeaderValue};
+use serde::{
+    de::{self, VariantAccess},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+const STRUCT_NAME: &str = "HeaderOp";
+const VARIANTS: &[&str] = &["set", "append", "remove", "rename"];
+
+/// One edit of a [`HeaderPatch`].
+///
+/// Human-readable formats write an operation as a map from its name to its fields, such as
+/// `{"remove": {"name": "server"}}`. Binary formats write an enum variant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeaderOp {
+    /// Replaces the values of `name` with `value`.
+    Set {
+        name: HeaderName,
+        value: HeaderValue,
+    },
+    /// Adds `value` after the values of `name`.
+    Append {
+        name: HeaderName,
+        value: HeaderValue,
+    },
+    /// Removes the values of `name`, if any.
+    Remove { name: HeaderName },
+    /// Moves the values of `from`, if any, to `to`, replacing the values of `to`.
+    Rename { from: HeaderName, to: HeaderName },
+}
+
+#[derive(Serialize)]
+struct BorrowedNameValue<'a> {
+    #[serde(with = "crate::header_name")]
+    name: &'a HeaderName,
+    #[serde(with = "crate::header_value")]
+    value: &'a HeaderValue,
+}
+
+#[derive(Serialize)]
+struct BorrowedName<'a> {
+    #[serde(with = "crate::header_name")]
+    name: &'a HeaderName,
+}
+
+#[derive(Serialize)]
+struct BorrowedRename<'a> {
+    #[serde(with = "crate::header_name")]
+    from: &'a HeaderName,
+    #[serde(with = "crate::header_name")]
+    to: &'a HeaderName,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NameValue {
+    #[serde(with = "crate::header_name")]
+    name: HeaderName,
+    #[serde(with = "crate::header_value")]
+    value: HeaderValue,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Name {
+    #[serde(with = "crate::header_name")]
+    name: HeaderName,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rename {
+    #[serde(with = "crate::header_name")]
+    from: HeaderName,
+    #[serde(with = "crate::header_name")]
+    to: HeaderName,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Op {
+    Set,
+    Append,
+    Remove,
+    Rename,
+}
+
+impl Serialize for HeaderOp {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        fn write<S: Serializer, T: Serialize>(
+            ser: S,
+            index: u32,
+            fields: &T,
+        ) -> Result<S::Ok, S::Error> {
+            let variant = VARIANTS[index as usize];
+            if ser.is_human_readable() {
+                let mut map = ser.serialize_map(Some(1))?;
+                map.serialize_entry(variant, fields)?;
+                map.end()
+            } else {
+                ser.serialize_newtype_variant(STRUCT_NAME, index, variant, fields)
+            }
+        }
+
+        match self {
+            HeaderOp::Set { name, value } => write(ser, 0, &BorrowedNameValue { name, value }),
+            HeaderOp::Append { name, value } => write(ser, 1, &BorrowedNameValue { name, value }),
+            HeaderOp::Remove { name } => write(ser, 2, &BorrowedName { name }),
+            HeaderOp::Rename { from, to } => write(ser, 3, &BorrowedRename { from, to }),
+        }
+    }
+}
+
+struct Visitor;
+
+impl<'de> de::Visitor<'de> for Visitor {
+    type Value = HeaderOp;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a header operation")
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        let op = match map.next_key::<Op>()? {
+            Some(Op::Set) => {
+                let NameValue { name, value } = map.next_value()?;
+                HeaderOp::Set { name, value }
+            }
+            Some(Op::Append) => {
+                let NameValue { name, value } = map.next_value()?;
+                HeaderOp::Append { name, value }
+            }
+            Some(Op::Remove) => HeaderOp::Remove {
+                name: map.next_value::<Name>()?.name,
+            },
+            Some(Op::Rename) => {
+                let Rename { from, to } = map.next_value()?;
+                HeaderOp::Rename { from, to }
+            }
+            None => return Err(de::Error::invalid_length(0, &self)),
+        };
+        if map.next_key::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::custom("expected a single header operation"));
+        }
+        Ok(op)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        let (op, variant) = data.variant::<Op>()?;
+        Ok(match op {
+            Op::Set => {
+                let NameValue { name, value } = variant.newtype_variant()?;
+                HeaderOp::Set { name, value }
+            }
+            Op::Append => {
+                let NameValue { name, value } = variant.newtype_variant()?;
+                HeaderOp::Append { name, value }
+            }
+            Op::Remove => HeaderOp::Remove {
+                name: variant.newtype_variant::<Name>()?.name,
+            },
+            Op::Rename => {
+                let Rename { from, to } = variant.newtype_variant()?;
+                HeaderOp::Rename { from, to }
+            }
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for HeaderOp {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        if de.is_human_readable() {
+            de.deserialize_map(Visitor)
+        } else {
+            de.deserialize_enum(STRUCT_NAME, VARIANTS, Visitor)
+        }
+    }
+}
+
+impl HeaderOp {
+    fn apply(&self, headers: &mut HeaderMap) {
+        match self {
+            HeaderOp::Set { name, value } => {
+                headers.insert(name, value.clone());
+            }
+            HeaderOp::Append { name, value } => {
+                headers.append(name, value.clone());
+            }
+            HeaderOp::Remove { name } => {
+                headers.remove(name);
+            }
+            HeaderOp::Rename { from, to } if from == to => {}
+            HeaderOp::Rename { from, to } => {
+                if let Entry::Occupied(e) = headers.entry(from) {
+                    let values: Vec<HeaderValue> = e.remove_entry_mult().1.collect();
+                    headers.remove(to);
+                    for value in values {
+                        headers.append(to, value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A list of edits to a [`HeaderMap`], applied in order.
+///
+/// A patch is written as a sequence of operations: `set` and `append` take a `name` and a
+/// `value`, `remove` takes a `name`, and `rename` takes `from` and `to`. Names and values use
+/// the [`header_name`](crate::header_name) and [`header_value`](crate::header_value)
+/// representations. Removing or renaming a header that isn't there does nothing.
+///
+/// [`diff`](Self::diff) computes the patch between two maps. Operations don't record the
+/// values they overwrite, so a patch is [inverted](Self::invert) against the map it applies
+/// to.
+///
+/// ```
+/// use http::{HeaderMap, HeaderValue};
+/// use http_serde_ext::HeaderPatch;
+///
+/// let yaml = "
+/// - set: {name: x-env, value: prod}
+/// - append: {name: via, value: 1.1 proxy}
+/// - remove: {name: server}
+/// - rename: {from: x-old, to: x-new}
+/// ";
+/// let patch: HeaderPatch = serde_yaml::from_str(yaml).unwrap();
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("server", HeaderValue::from_static("origin"));
+/// headers.insert("x-old", HeaderValue::from_static("1"));
+/// let original = headers.clone();
+///
+/// patch.apply(&mut headers);
+/// assert_eq!(headers["x-env"], "prod");
+/// assert_eq!(headers["via"], "1.1 proxy");
+/// assert_eq!(headers["x-new"], "1");
+/// assert!(!headers.contains_key("server"));
+///
+/// patch.invert(&original).apply(&mut headers);
+/// assert_eq!(headers, original);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct HeaderPatch(Vec<HeaderOp>);
+
+impl HeaderPatch {
+    /// Creates an empty patch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an operation replacing the values of `name` with `value`.
+    pub fn set(self, name: HeaderName, value: HeaderValue) -> Self {
+        self.op(HeaderOp::Set { name, value })
+    }
+
+    /// Adds an operation adding `value` after the values of `name`.
+    pub fn append(self, name: HeaderName, value: HeaderValue) -> Self {
+        self.op(HeaderOp::Append { name, value })
+    }
+
+    /// Adds an operation removing `name`.
+    pub fn remove(self, name: HeaderName) -> Self {
+        self.op(HeaderOp::Remove { name })
+    }
+
+    /// Adds an operation moving the values of `from` to `to`.
+    pub fn rename(self, from: HeaderName, to: HeaderName) -> Self {
+        self.op(HeaderOp::Rename { from, to })
+    }
+
+    /// Adds `op`.
+    pub fn op(mut self, op: HeaderOp) -> Self {
+        self.0.push(op);
+        self
+    }
+
+    /// Returns the operations of the patch.
+    pub fn ops(&self) -> &[HeaderOp] {
+        &self.0
+    }
+
+    /// Returns `true` if the patch has no operations.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Applies the operations to `headers`, in order.
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        for op in &self.0 {
+            op.apply(headers);
+        }
+    }
+
+    /// Returns the patch that turns `from` into `to`.
+    ///
+    /// Headers are visited in sorted order. A header whose values differ is set to its first
+    /// value, then the others are appended.
+    pub fn diff(from: &HeaderMap, to: &HeaderMap) -> Self {
+        let mut names: Vec<&HeaderName> = from.keys().chain(to.keys()).collect();
+        names.sort_unstable_by(|a, b| a.as_str().cmp(b.as_str()));
+        names.dedup();
+
+        let mut patch = Self::new();
+        for name in names {
+            let old = from.get_all(name);
+            let new = to.get_all(name);
+            if old.iter().eq(new.iter()) {
+                continue;
+            }
+            let mut values = new.iter();
+            match values.next() {
+                Some(first) => {
+                    patch = patch.set(name.clone(), first.clone());
+                    for value in values {
+                        patch = patch.append(name.clone(), value.clone());
+                    }
+                }
+                None => patch = patch.remove(name.clone()),
+            }
+        }
+        patch
+    }
+
+    /// Returns the patch that undoes this one after it is applied to `base`.
+    pub fn invert(&self, base: &HeaderMap) -> Self {
+        let mut patched = base.clone();
+        self.apply(&mut patched);
+        Self::diff(&patched, base)
+    }
+}
+
+impl From<Vec<HeaderOp>> for HeaderPatch {
+    fn from(ops: Vec<HeaderOp>) -> Self {
+        Self(ops)
+    }
+}
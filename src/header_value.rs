@@ -1,38 +1,320 @@
-use serde::{ser, Deserializer, Serializer};
+use std::{fmt, str};
+
+use http::HeaderName;
+use serde::{
+    de,
+    ser::{SerializeMap, SerializeTuple},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{base64, limits};
 
 type Type = http::HeaderValue;
 const EXPECT_MESSAGE: &str = "a header value";
+const VALUE_KEY: &str = "value";
+const BASE64_KEY: &str = "base64";
+const SENSITIVE_KEY: &str = "sensitive";
+const KEYS: &[&str] = &[VALUE_KEY, BASE64_KEY, SENSITIVE_KEY];
+
+struct BorrowedBytes<'a>(&'a [u8]);
+
+impl<'a> Serialize for BorrowedBytes<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_bytes(self.0)
+    }
+}
+
+// Human-readable formats write values as plain strings, or as `{"base64": "..."}` when they
+// aren't valid UTF-8. With `with_sensitive`, sensitive values are written as
+// `{"sensitive": true, "value": "..."}` or `{"base64": "...", "sensitive": true}`.
+// Binary formats write a `(bytes, sensitive)` tuple.
+pub(crate) fn serialize_value<S>(val: &Type, with_sensitive: bool, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if !ser.is_human_readable() {
+        let mut tuple = ser.serialize_tuple(2)?;
+        tuple.serialize_element(&BorrowedBytes(val.as_bytes()))?;
+        tuple.serialize_element(&val.is_sensitive())?;
+        return tuple.end();
+    }
+
+    let sensitive = with_sensitive && val.is_sensitive();
+    match str::from_utf8(val.as_bytes()) {
+        Ok(val) if !sensitive => ser.serialize_str(val),
+        Ok(val) => {
+            let mut map = ser.serialize_map(Some(2))?;
+            map.serialize_entry(SENSITIVE_KEY, &true)?;
+            map.serialize_entry(VALUE_KEY, val)?;
+            map.end()
+        }
+        Err(_) => {
+            let mut map = ser.serialize_map(Some(1 + usize::from(sensitive)))?;
+            map.serialize_entry(BASE64_KEY, &base64::encode(val.as_bytes()))?;
+            if sensitive {
+                map.serialize_entry(SENSITIVE_KEY, &true)?;
+            }
+            map.end()
+        }
+    }
+}
 
 pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
-    if ser.is_human_readable() {
-        use ser::Error;
-        ser.serialize_str(val.to_str().map_err(Error::custom)?)
-    } else {
-        ser.serialize_bytes(val.as_ref())
+    serialize_value(val, false, ser)
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Visitor<'a> {
+    // The header the value belongs to, named in errors.
+    pub(crate) name: Option<&'a HeaderName>,
+}
+
+impl<'a> Visitor<'a> {
+    fn error<E: de::Error>(&self, err: impl fmt::Display) -> E {
+        match self.name {
+            Some(name) => E::custom(format_args!("invalid value for header {name}: {err}")),
+            None => E::custom(err),
+        }
+    }
+
+    fn check_len<E: de::Error>(&self, len: usize) -> Result<(), E> {
+        limits::check_header_value_len::<E>(len).map_err(|err| self.error(err))
+    }
+}
+
+impl<'de, 'a> de::Visitor<'de> for Visitor<'a> {
+    type Value = Type;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(formatter, "a value for header {name}"),
+            None => formatter.write_str(EXPECT_MESSAGE),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        self.check_len(val.len())?;
+        val.try_into().map_err(|err| self.error(err))
+    }
+
+    fn visit_string<E: de::Error>(self, val: String) -> Result<Self::Value, E> {
+        self.check_len(val.len())?;
+        val.try_into().map_err(|err| self.error(err))
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        self.check_len(val.len())?;
+        val.try_into().map_err(|err| self.error(err))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, val: Vec<u8>) -> Result<Self::Value, E> {
+        self.check_len(val.len())?;
+        val.try_into().map_err(|err| self.error(err))
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        let mut value = None;
+        let mut sensitive = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                VALUE_KEY | BASE64_KEY if value.is_some() => {
+                    return Err(self.error("expected one of `value` or `base64`"));
+                }
+                VALUE_KEY => value = Some(self.visit_string(map.next_value()?)?),
+                BASE64_KEY => {
+                    let val = base64::decode(&map.next_value::<String>()?);
+                    value = Some(self.visit_byte_buf(val.map_err(|err| self.error(err))?)?);
+                }
+                SENSITIVE_KEY if sensitive.is_some() => {
+                    return Err(de::Error::duplicate_field(SENSITIVE_KEY));
+                }
+                SENSITIVE_KEY => sensitive = Some(map.next_value()?),
+                _ => return Err(de::Error::unknown_field(&key, KEYS)),
+            }
+        }
+        let mut value = value.ok_or_else(|| self.error("expected one of `value` or `base64`"))?;
+        value.set_sensitive(sensitive.unwrap_or(false));
+        Ok(value)
+    }
+}
+
+struct Bytes(Type);
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_byte_buf(Visitor { name: None }).map(Bytes)
     }
 }
 
-create_visitor!(
-    Visitor,
-    Type,
-    EXPECT_MESSAGE,
-    (visit_str, &str),
-    (visit_string, String),
-    (visit_bytes, &[u8]),
-    (visit_byte_buf, Vec<u8>)
-);
+struct CompactVisitor;
+
+impl<'de> de::Visitor<'de> for CompactVisitor {
+    type Value = Type;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(EXPECT_MESSAGE)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut value: Bytes = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let sensitive = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        value.0.set_sensitive(sensitive);
+        Ok(value.0)
+    }
+}
 
 pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
 where
     D: Deserializer<'de>,
 {
     if de.is_human_readable() {
-        de.deserialize_string(Visitor)
+        de.deserialize_any(Visitor { name: None })
     } else {
-        de.deserialize_byte_buf(Visitor)
+        de.deserialize_tuple(2, CompactVisitor)
     }
 }
 
 derive_extension_types!(super::Type);
 derive_hash_types!(super::Type);
 derive_ord_types!(super::Type);
+
+/// Serializes a [`HeaderValue`](http::HeaderValue) keeping its
+/// [sensitivity](http::HeaderValue::is_sensitive) in human-readable formats.
+///
+/// Sensitive values are written as `{"sensitive": true, "value": "..."}`, or with a `base64`
+/// field in place of `value` when they aren't valid UTF-8. Other values are written as by
+/// [`header_value`](super). Binary formats always keep the flag, and deserializing accepts the
+/// same shapes as [`header_value`](super).
+///
+/// ```
+/// use http::HeaderValue;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::header_value::with_sensitive")]
+///     token: HeaderValue,
+/// }
+///
+/// let mut token = HeaderValue::from_static("Bearer secret");
+/// token.set_sensitive(true);
+///
+/// let json = serde_json::to_string(&MyStruct { token }).unwrap();
+/// assert_eq!(json, r#"{"token":{"sensitive":true,"value":"Bearer secret"}}"#);
+/// let de: MyStruct = serde_json::from_str(&json).unwrap();
+/// assert!(de.token.is_sensitive());
+/// ```
+pub mod with_sensitive {
+    use serde::{Deserializer, Serializer};
+
+    use super::Type;
+
+    pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        super::serialize_value(val, true, ser)
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize(de)
+    }
+
+    derive_extension_types!(super::Type);
+    derive_hash_types!(super::Type);
+    derive_ord_types!(super::Type);
+}
+
+/// Serializes a [`HeaderValue`](http::HeaderValue) as an RFC 8941 structured field in
+/// human-readable formats.
+///
+/// The submodules parse the value as a [`list`](structured::list),
+/// [`dictionary`](structured::dictionary) or [`item`](structured::item):
+/// - integers, decimals, strings and booleans are written as native values
+/// - tokens are written as `{"token": "..."}` and byte sequences as `{"bytes": "<base64>"}`
+/// - items with parameters are written as `{"value": ..., "params": {...}}`
+/// - inner lists are written as sequences, or as `{"items": [...], "params": {...}}` with
+///   parameters
+///
+/// Values that don't parse are written as `{"$raw": ...}` holding the
+/// [`header_value`](super) representation. Deserializing writes the field back in its
+/// canonical textual form. Binary formats use the [`header_value`](super) representation.
+///
+/// ```
+/// use http::HeaderValue;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::header_value::structured::dictionary")]
+///     priority: HeaderValue,
+///     #[serde(with = "http_serde_ext::header_value::structured::list")]
+///     cache_status: HeaderValue,
+/// }
+///
+/// let json = r#"{"priority":{"u":5,"i":true},"cache_status":[{"value":{"token":"ExampleCache"},"params":{"hit":true,"ttl":376}}]}"#;
+/// let de: MyStruct = serde_json::from_str(json).unwrap();
+/// assert_eq!(de.priority, "u=5, i");
+/// assert_eq!(de.cache_status, "ExampleCache;hit;ttl=376");
+/// assert_eq!(serde_json::to_string(&de).unwrap(), json);
+/// ```
+pub mod structured {
+    macro_rules! structured_mod {
+        ($name:ident, $kind:ident, $doc:literal) => {
+            #[doc = $doc]
+            pub mod $name {
+                use serde::{Deserializer, Serializer};
+
+                use super::super::Type;
+                use crate::structured_field::{self, Kind};
+
+                pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
+                    if ser.is_human_readable() {
+                        structured_field::serialize_values(Kind::$kind, &[val], ser)
+                    } else {
+                        super::super::serialize(val, ser)
+                    }
+                }
+
+                pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    if de.is_human_readable() {
+                        structured_field::deserialize_value(Kind::$kind, de)
+                    } else {
+                        super::super::deserialize(de)
+                    }
+                }
+
+                derive_extension_types!(super::Type);
+            }
+        };
+    }
+
+    structured_mod!(
+        list,
+        List,
+        "Serializes a [`HeaderValue`](http::HeaderValue) as a structured field list."
+    );
+    structured_mod!(
+        dictionary,
+        Dictionary,
+        "Serializes a [`HeaderValue`](http::HeaderValue) as a structured field dictionary."
+    );
+    structured_mod!(
+        item,
+        Item,
+        "Serializes a [`HeaderValue`](http::HeaderValue) as a structured field item."
+    );
+}
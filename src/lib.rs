@@ -15,6 +15,19 @@
 //! - [`Version`](version)
 //! - Generic [`HeaderMap<T>`](header_map_generic) where the item is not a `HeaderValue`
 //!
+//! Also provides:
+//! - [`StatusCodeSet`] to match status codes, classes and ranges
+//! - [`RedactionPolicy`] to keep credentials out of serialized headers
+//! - [`header_value::structured`] and [`header_map::structured`] for RFC 8941 structured fields
+//! - [`header_map::cookies`] for `Cookie` and `Set-Cookie` values as objects
+//! - [`DuplicatePolicy`] for header names that appear more than once
+//! - [`header_map::pairs`] for headers as name/value pairs, optionally in [`HeaderOrder`]
+//! - [`header_map::sorted`], [`request::sorted`] and [`response::sorted`] for sorted header names
+//! - [`Limits`] to bound what untrusted input may allocate
+//! - [`header_map::MergeInto`], [`request::MergeInto`] and [`response::MergeInto`] to
+//!   deserialize onto an existing value
+//! - [`HeaderPatch`] to apply, invert and diff header edits
+//!
 //! Allows serializing and deserializing the above types wrapped in the following `std` container types:
 //! - [`Option`]
 //! - [`Result`] in the `Ok` position
@@ -23,8 +36,9 @@
 //! - [`LinkedList`](std::collections::LinkedList)
 //! - [`HashMap`](std::collections::HashMap) as the `Key` for all except `HeaderMap`, `Request`, and `Response`. As the `Value` for all types.
 //! - [`BTreeMap`](std::collections::BTreeMap)  as the `Key` only for `HeaderValue`, `StatusCode`, and `Version`. As the `Value` for all types.
+//!   Other types can be used as the `Key` when wrapped in [`Ordered`].
 //! - [`HashSet`](std::collections::HashSet) for all except `HeaderMap`, `Request`, and `Response`
-//! - [`BTreeSet`](std::collections::BTreeSet) only for `HeaderValue`, `StatusCode`, and `Version`
+//! - [`BTreeSet`](std::collections::BTreeSet) only for `HeaderValue`, `StatusCode`, and `Version`, or other types wrapped in [`Ordered`]
 //!
 //! ## Usage
 //!
@@ -81,9 +95,43 @@
 //! let deserialized: Vec<http::Response<()>> =
 //!     http_serde_ext::response::vec::deserialize(serialized).unwrap();
 //! ```
+//!
+//! ## Features
+//!
+//! - `hyper`: [`Response`](response)s keep a non-canonical `hyper::ext::ReasonPhrase`
+//!   extension. It is written as an optional `reason` field of the head and put back into
+//!   the extensions on deserialize. Binary formats hold the field with or without the feature,
+//!   so builds with and without it read each other's output. This changed the binary layout of
+//!   response heads: those written by 1.0.2 or earlier can't be read back.
+//! - `headers`: [`typed_header`] serializes any `headers::Header` implementation,
+//!   such as `ContentType` or `Authorization<Bearer>`, through its `header_value` representation.
+//! - `sha2`: [`RedactionPolicy::hash_prefix`] keeps the first hex digits of the SHA-256 of a
+//!   redacted value in its placeholder.
 
 #[macro_use]
 mod macros;
+mod base64;
+mod cookie;
+mod duplicate_policy;
+mod head;
+mod header_case_map;
+mod header_order;
+mod header_patch;
+mod limits;
+mod ordered;
+mod redaction;
+mod status_code_set;
+mod structured_field;
+
+pub use duplicate_policy::DuplicatePolicy;
+pub use header_case_map::HeaderCaseMap;
+pub use header_order::HeaderOrder;
+pub use header_patch::{HeaderOp, HeaderPatch};
+pub use limits::Limits;
+pub use ordered::Ordered;
+pub use redaction::RedactionPolicy;
+pub use status_code_set::StatusCodeSet;
+pub use structured_field::Kind as StructuredKind;
 
 #[derive(serde::Serialize)]
 struct BorrowedNameWrapper<'a>(#[serde(with = "crate::header_name")] &'a http::HeaderName);
@@ -91,40 +139,6 @@ struct BorrowedNameWrapper<'a>(#[serde(with = "crate::header_name")] &'a http::H
 #[derive(serde::Deserialize)]
 struct NameWrapper(#[serde(with = "crate::header_name")] http::HeaderName);
 
-#[derive(serde::Deserialize)]
-#[serde(untagged)]
-enum Either<T> {
-    One(T),
-    Many(Vec<T>),
-}
-
-#[inline]
-fn insert_header_values<'a, M, T>(
-    map: &mut http::HeaderMap<T>,
-    key: http::HeaderName,
-    mut values: impl Iterator<Item = T>,
-) -> Result<(), M::Error>
-where
-    M: serde::de::MapAccess<'a>,
-{
-    if let http::header::Entry::Vacant(e) = map.entry(key) {
-        if let Some(first) = values.next() {
-            let mut e = e.insert_entry(first);
-
-            for val in values {
-                e.append(val);
-            }
-        } else {
-            return Err(serde::de::Error::custom(format!(
-                "no value for header {}",
-                e.key()
-            )));
-        }
-    }
-
-    Ok(())
-}
-
 macro_rules! doc_mod {
     { $ty:ty, $path:ident$(, $generic:ident)? } => {
         #[doc = concat!(" [`Serialize`](serde::Serialize)/[`Deserialize`](serde::Deserialize) for [`http::", stringify!($ty), "`]")]
@@ -291,3 +305,35 @@ doc_mod_hash!(Scheme, scheme, "uri::");
 doc_mod_ord_and_hash!(StatusCode, status_code);
 doc_mod_hash!(Uri, uri);
 doc_mod_ord_and_hash!(Version, version);
+
+/// [`Serialize`](serde::Serialize)/[`Deserialize`](serde::Deserialize) for any
+/// [`headers::Header`] implementation.
+///
+/// Values are [encoded](headers::Header::encode) and written in the
+/// [`header_value`] representation, as a sequence when the header encodes to several values.
+/// Deserializing [decodes](headers::Header::decode) them back and fails if the header doesn't
+/// parse.
+///
+/// ```
+/// use headers::{CacheControl, ContentType, ETag};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::typed_header")]
+///     content_type: ContentType,
+///
+///     #[serde(with = "http_serde_ext::typed_header::option", default)]
+///     cache_control: Option<CacheControl>,
+///
+///     #[serde(with = "http_serde_ext::typed_header::vec")]
+///     etags: Vec<ETag>,
+/// }
+///
+/// let json = r#"{"content_type":"application/json","cache_control":"no-cache","etags":["\"xyzzy\""]}"#;
+/// let de: MyStruct = serde_json::from_str(json).unwrap();
+/// assert_eq!(de.content_type, ContentType::json());
+/// assert_eq!(serde_json::to_string(&de).unwrap(), json);
+/// ```
+#[cfg(feature = "headers")]
+pub mod typed_header;
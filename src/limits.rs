@@ -0,0 +1,189 @@
+use std::cell::Cell;
+
+use serde::de;
+
+thread_local! {
+    static CURRENT: Cell<Limits> = const { Cell::new(Limits::new()) };
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Bounds on what deserializing may allocate, for input from untrusted sources.
+///
+/// Limits apply to everything deserialized on the current thread inside [`scope`](Self::scope):
+/// [`header_map`](crate::header_map) and its variants, [`uri`](crate::uri),
+/// [`request`](crate::request), [`response`](crate::response) and the container modules such as
+/// `vec` or `hash_map`. Exceeding one fails with a serde error.
+///
+/// Outside a scope, or with [`Limits::new`], only preallocation is limited: collections
+/// reserve at most [4096](Self::max_preallocation) entries up front, however many the input
+/// claims to hold.
+///
+/// ```
+/// use http::HeaderMap;
+/// use http_serde_ext::Limits;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct MyStruct {
+///     #[serde(with = "http_serde_ext::header_map")]
+///     headers: HeaderMap,
+/// }
+///
+/// let limits = Limits::new().max_headers(1);
+/// let json = r#"{"headers":{"accept":"*/*","host":"example.com"}}"#;
+/// let err = limits
+///     .scope(|| serde_json::from_str::<MyStruct>(json))
+///     .err()
+///     .unwrap();
+/// assert!(err.to_string().starts_with("more than 1 headers"));
+/// assert!(serde_json::from_str::<MyStruct>(json).is_ok());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    max_headers: usize,
+    max_header_value_len: usize,
+    max_uri_len: usize,
+    max_depth: usize,
+    max_preallocation: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Limits {
+    /// Creates limits that only cap preallocation.
+    pub const fn new() -> Self {
+        Self {
+            max_headers: usize::MAX,
+            max_header_value_len: usize::MAX,
+            max_uri_len: usize::MAX,
+            max_depth: usize::MAX,
+            max_preallocation: 4096,
+        }
+    }
+
+    /// Sets how many header values a header map may hold.
+    pub const fn max_headers(mut self, max: usize) -> Self {
+        self.max_headers = max;
+        self
+    }
+
+    /// Sets the length in bytes of the longest header value.
+    pub const fn max_header_value_len(mut self, max: usize) -> Self {
+        self.max_header_value_len = max;
+        self
+    }
+
+    /// Sets the length in bytes of the longest URI.
+    pub const fn max_uri_len(mut self, max: usize) -> Self {
+        self.max_uri_len = max;
+        self
+    }
+
+    /// Sets how deeply requests and responses may be nested in each other's bodies. `1`
+    /// allows a request or response whose body holds neither.
+    pub const fn max_depth(mut self, max: usize) -> Self {
+        self.max_depth = max;
+        self
+    }
+
+    /// Sets how many entries a collection reserves before any is deserialized.
+    pub const fn max_preallocation(mut self, max: usize) -> Self {
+        self.max_preallocation = max;
+        self
+    }
+
+    /// Returns the limits in effect on the current thread.
+    pub fn current() -> Self {
+        CURRENT.with(Cell::get)
+    }
+
+    /// Runs `f` with these limits in effect on the current thread, then restores the previous
+    /// ones.
+    pub fn scope<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Limits);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.with(|current| current.set(self.0));
+            }
+        }
+
+        let _restore = Restore(CURRENT.with(|current| current.replace(self)));
+        f()
+    }
+}
+
+// How many entries to reserve for a collection whose input claims to hold `hint`.
+pub(crate) fn capacity(hint: Option<usize>) -> usize {
+    hint.unwrap_or(0).min(Limits::current().max_preallocation)
+}
+
+// Same as `capacity`, for a header map. `HeaderMap::with_capacity` reserves a third more
+// than asked and panics past 32768.
+pub(crate) fn header_map_capacity(hint: Option<usize>) -> usize {
+    capacity(hint).min((1 << 15) / 4 * 3)
+}
+
+// Fails if a header map already holding `len` values can't take another.
+pub(crate) fn check_headers<E: de::Error>(len: usize) -> Result<(), E> {
+    let max = Limits::current().max_headers;
+    if len >= max {
+        return Err(de::Error::custom(format!("more than {max} headers")));
+    }
+    Ok(())
+}
+
+// The error for a header map that `http` can't grow any further, at about 24576 names
+// whatever the limits say.
+pub(crate) fn header_map_full<E: de::Error>(_: impl std::fmt::Display) -> E {
+    de::Error::custom("too many header names for a header map")
+}
+
+pub(crate) fn check_header_value_len<E: de::Error>(len: usize) -> Result<(), E> {
+    let max = Limits::current().max_header_value_len;
+    if len > max {
+        return Err(de::Error::custom(format!(
+            "header value is longer than {max} bytes"
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_uri_len<E: de::Error>(len: usize) -> Result<(), E> {
+    let max = Limits::current().max_uri_len;
+    if len > max {
+        return Err(de::Error::custom(format!("uri is longer than {max} bytes")));
+    }
+    Ok(())
+}
+
+// Counts a request or response being deserialized until dropped.
+pub(crate) struct Depth(());
+
+impl Depth {
+    pub(crate) fn enter<E: de::Error>() -> Result<Self, E> {
+        let max = Limits::current().max_depth;
+        let depth = DEPTH.with(|depth| {
+            let val = depth.get() + 1;
+            depth.set(val);
+            val
+        });
+        let guard = Depth(());
+        if depth > max {
+            return Err(de::Error::custom(format!(
+                "requests and responses are nested more than {max} deep"
+            )));
+        }
+        Ok(guard)
+    }
+}
+
+impl Drop for Depth {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
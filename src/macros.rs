@@ -1,10 +1,15 @@
 macro_rules! serde_option {
-    ($ty:ty$(, $generic:ident)?) => {
+    ($ty:ty$(, $generic:ident: [$($ser:tt)+], [$($de:tt)+])?) => {
         pub mod option {
-            #[derive(serde::Serialize)]
-            struct Temp<'a$(, $generic: serde::Serialize)?>(#[serde(with = "super")] &'a $ty);
+            struct Temp<'a$(, $generic: $($ser)+)?>(&'a $ty);
 
-            pub fn serialize<$($generic: serde::Serialize, )?S: serde::Serializer>(
+            impl<'a$(, $generic: $($ser)+)?> serde::Serialize for Temp<'a$(, $generic)?> {
+                fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                    super::serialize(self.0, ser)
+                }
+            }
+
+            pub fn serialize<$($generic: $($ser)+, )?S: serde::Serializer>(
                 val: &Option<$ty>,
                 ser: S,
             ) -> Result<S::Ok, S::Error> {
@@ -14,11 +19,11 @@ macro_rules! serde_option {
                 }
             }
 
-            struct Visitor$(<$generic: for<'a> serde::Deserialize<'a>>)? {
+            struct Visitor$(<$generic: $($de)+>)? {
                 $(ph: std::marker::PhantomData<$generic>,)?
             }
 
-            impl<'de$(, $generic: for<'a> serde::Deserialize<'a>)?> serde::de::Visitor<'de> for Visitor$(<$generic>)? {
+            impl<'de$(, $generic: $($de)+)?> serde::de::Visitor<'de> for Visitor$(<$generic>)? {
                 type Value = Option<$ty>;
 
                 fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -50,7 +55,7 @@ macro_rules! serde_option {
             pub fn deserialize<'de, D$(, $generic)?>(de: D) -> Result<Option<$ty>, D::Error>
             where
                 D: serde::Deserializer<'de>,
-                $($generic: for<'a> serde::Deserialize<'a>,)?
+                $($generic: $($de)+,)?
             {
                 de.deserialize_option(Visitor$(::<$generic>)? {
                     $(ph: std::marker::PhantomData::<$generic>,)?
@@ -61,18 +66,23 @@ macro_rules! serde_option {
 }
 
 macro_rules! serde_result {
-    ($ty:ty$(, $generic:ident)?) => {
+    ($ty:ty$(, $generic:ident: [$($ser:tt)+], [$($de:tt)+])?) => {
         pub mod result {
             const NAME: &str = "Result";
             const VARIANTS: &[&str] = &["Ok", "Err"];
 
             #[derive(serde::Deserialize)]
-            struct TempDe$(<$generic: for<'a> serde::Deserialize<'a>>)?(#[serde(with = "super")] $ty);
+            struct TempDe$(<$generic: $($de)+>)?(#[serde(with = "super")] $ty);
 
-            #[derive(serde::Serialize)]
-            struct TempSer<'a$(, $generic: serde::Serialize)?>(#[serde(with = "super")] &'a $ty);
+            struct TempSer<'a$(, $generic: $($ser)+)?>(&'a $ty);
 
-            pub fn serialize<$($generic: serde::Serialize, )?S: serde::Serializer, E: serde::Serialize>(
+            impl<'a$(, $generic: $($ser)+)?> serde::Serialize for TempSer<'a$(, $generic)?> {
+                fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                    super::serialize(self.0, ser)
+                }
+            }
+
+            pub fn serialize<$($generic: $($ser)+, )?S: serde::Serializer, E: serde::Serialize>(
                 val: &Result<$ty, E>,
                 ser: S,
             ) -> Result<S::Ok, S::Error> {
@@ -145,12 +155,12 @@ macro_rules! serde_result {
                 }
             }
 
-            struct Visitor<$($generic: for<'a> serde::Deserialize<'a>, )?E: for<'a> serde::Deserialize<'a>> {
+            struct Visitor<$($generic: $($de)+, )?E: for<'a> serde::Deserialize<'a>> {
                 phe: std::marker::PhantomData<E>,
                 $(ph: std::marker::PhantomData<$generic>,)?
             }
 
-            impl<'de$(, $generic: for<'a> serde::Deserialize<'a>)?, E: for<'a> serde::Deserialize<'a>> serde::de::Visitor<'de> for Visitor<$($generic, )?E> {
+            impl<'de$(, $generic: $($de)+)?, E: for<'a> serde::Deserialize<'a>> serde::de::Visitor<'de> for Visitor<$($generic, )?E> {
                 type Value = Result<$ty, E>;
 
                 fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -172,7 +182,7 @@ macro_rules! serde_result {
             where
                 D: serde::Deserializer<'de>,
                 E: for<'a> serde::Deserialize<'a>,
-                $($generic: for<'a> serde::Deserialize<'a>,)?
+                $($generic: $($de)+,)?
             {
                 de.deserialize_enum(NAME, &VARIANTS, Visitor::<$($generic, )?E> {
                     phe: std::marker::PhantomData::<E>,
@@ -184,16 +194,21 @@ macro_rules! serde_result {
 }
 
 macro_rules! serde_seq {
-    ($seq:ty, $ty:ty, $create:expr, $insert:ident, $name:ident$(, $generic:ident)?) => {
+    ($seq:ty, $ty:ty, $create:expr, $insert:ident, $name:ident$(, $generic:ident: [$($ser:tt)+], [$($de:tt)+])?) => {
         pub mod $name {
             #[derive(serde::Deserialize)]
-            struct TempDe$(<$generic: for<'a> serde::Deserialize<'a>>)?(#[serde(with = "super")] $ty);
+            struct TempDe$(<$generic: $($de)+>)?(#[serde(with = "super")] $ty);
 
-            #[derive(serde::Serialize)]
-            struct TempSer<'a$(, $generic: serde::Serialize)?>(#[serde(with = "super")] &'a $ty);
+            struct TempSer<'a$(, $generic: $($ser)+)?>(&'a $ty);
+
+            impl<'a$(, $generic: $($ser)+)?> serde::Serialize for TempSer<'a$(, $generic)?> {
+                fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                    super::serialize(self.0, ser)
+                }
+            }
 
             #[allow(clippy::mutable_key_type)]
-            pub fn serialize<$($generic: serde::Serialize, )?S: serde::Serializer>(
+            pub fn serialize<$($generic: $($ser)+, )?S: serde::Serializer>(
                 val: &$seq,
                 ser: S,
             ) -> Result<S::Ok, S::Error> {
@@ -205,11 +220,11 @@ macro_rules! serde_seq {
                 serde::ser::SerializeSeq::end(seq)
             }
 
-            struct Visitor$(<$generic: for<'a> serde::Deserialize<'a>>)? {
+            struct Visitor$(<$generic: $($de)+>)? {
                 $(ph: std::marker::PhantomData<$generic>,)?
             }
 
-            impl<'de$(, $generic: for<'a> serde::Deserialize<'a>)?> serde::de::Visitor<'de> for Visitor$(<$generic>)? {
+            impl<'de$(, $generic: $($de)+)?> serde::de::Visitor<'de> for Visitor$(<$generic>)? {
                 type Value = $seq;
 
                 fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -221,7 +236,7 @@ macro_rules! serde_seq {
                     V: serde::de::SeqAccess<'de>,
                 {
                     #[allow(clippy::redundant_closure_call, clippy::mutable_key_type)]
-                    let mut ret = $create(seq.size_hint().unwrap_or_default());
+                    let mut ret = $create(crate::limits::capacity(seq.size_hint()));
                     while let Some(val) = seq.next_element::<TempDe$(<$generic>)?>()? {
                         ret.$insert(val.0);
                     }
@@ -232,7 +247,7 @@ macro_rules! serde_seq {
             pub fn deserialize<'de, D$(, $generic)?>(de: D) -> Result<$seq, D::Error>
             where
                 D: serde::Deserializer<'de>,
-                $($generic: for<'a> serde::Deserialize<'a>,)?
+                $($generic: $($de)+,)?
             {
                 de.deserialize_seq(Visitor$(::<$generic>)? {
                     $(ph: std::marker::PhantomData::<$generic>,)?
@@ -243,16 +258,21 @@ macro_rules! serde_seq {
 }
 
 macro_rules! serde_map {
-    ($map:ty, $($bounds:path,)+, $key:ident, $ty:ty, $create:expr, $insert:ident, $name:ident$(, $generic:ident)?) => {
+    ($map:ty, $($bounds:path,)+, $key:ident, $ty:ty, $create:expr, $insert:ident, $name:ident$(, $generic:ident: [$($ser:tt)+], [$($de:tt)+])?) => {
         pub mod $name {
 
             #[derive(serde::Deserialize)]
-            struct TempDe$(<$generic: for<'a> serde::Deserialize<'a>>)?(#[serde(with = "super")] $ty);
+            struct TempDe$(<$generic: $($de)+>)?(#[serde(with = "super")] $ty);
+
+            struct TempSer<'a$(, $generic: $($ser)+)?>(&'a $ty);
 
-            #[derive(serde::Serialize)]
-            struct TempSer<'a$(, $generic: serde::Serialize)?>(#[serde(with = "super")] &'a $ty);
+            impl<'a$(, $generic: $($ser)+)?> serde::Serialize for TempSer<'a$(, $generic)?> {
+                fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                    super::serialize(self.0, ser)
+                }
+            }
 
-            pub fn serialize<$($generic: serde::Serialize, )?$key: serde::Serialize, S: serde::Serializer>(
+            pub fn serialize<$($generic: $($ser)+, )?$key: serde::Serialize, S: serde::Serializer>(
                 val: &$map,
                 ser: S,
             ) -> Result<S::Ok, S::Error> {
@@ -264,12 +284,12 @@ macro_rules! serde_map {
                 serde::ser::SerializeMap::end(map)
             }
 
-            struct Visitor<$key: for<'a> serde::Deserialize<'a>$(, $generic: for<'a> serde::Deserialize<'a>)?> {
+            struct Visitor<$key: for<'a> serde::Deserialize<'a>$(, $generic: $($de)+)?> {
                 ph_k: std::marker::PhantomData<$key>,
                 $(ph: std::marker::PhantomData<$generic>,)?
             }
 
-            impl<'de$(, $generic: for<'a> serde::Deserialize<'a>)?, $key: for<'a> serde::Deserialize<'a>$( + $bounds)+> serde::de::Visitor<'de> for Visitor<$key, $($generic)?> {
+            impl<'de$(, $generic: $($de)+)?, $key: for<'a> serde::Deserialize<'a>$( + $bounds)+> serde::de::Visitor<'de> for Visitor<$key, $($generic)?> {
                 type Value = $map;
 
                 fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -282,7 +302,7 @@ macro_rules! serde_map {
                 {
 
                     #[allow(clippy::redundant_closure_call)]
-                    let mut ret = $create(map.size_hint().unwrap_or_default());
+                    let mut ret = $create(crate::limits::capacity(map.size_hint()));
                     while let Some((k, val)) = map.next_entry::<$key, TempDe$(<$generic>)?>()? {
                         ret.$insert(k, val.0);
                     }
@@ -293,7 +313,7 @@ macro_rules! serde_map {
             pub fn deserialize<'de, D$(, $generic)?, $key: for<'a> serde::Deserialize<'a>$( + $bounds)+>(de: D) -> Result<$map, D::Error>
             where
                 D: serde::Deserializer<'de>,
-                $($generic: for<'a> serde::Deserialize<'a>,)?
+                $($generic: $($de)+,)?
             {
                 de.deserialize_map(Visitor$(::<$key, $generic>)? {
                     ph_k: std::marker::PhantomData::<$key>,
@@ -305,17 +325,22 @@ macro_rules! serde_map {
 }
 
 macro_rules! serde_map_key {
-    ($map:ty, $val:ident, $ty:ty, $create:expr, $insert:ident, $name:ident$(, $generic:ident)?) => {
+    ($map:ty, $val:ident, $ty:ty, $create:expr, $insert:ident, $name:ident$(, $generic:ident: [$($ser:tt)+], [$($de:tt)+])?) => {
         pub mod $name {
 
             #[derive(serde::Deserialize)]
-            struct TempDe$(<$generic: for<'a> serde::Deserialize<'a>>)?(#[serde(with = "super")] $ty);
+            struct TempDe$(<$generic: $($de)+>)?(#[serde(with = "super")] $ty);
+
+            struct TempSer<'a$(, $generic: $($ser)+)?>(&'a $ty);
 
-            #[derive(serde::Serialize)]
-            struct TempSer<'a$(, $generic: serde::Serialize)?>(#[serde(with = "super")] &'a $ty);
+            impl<'a$(, $generic: $($ser)+)?> serde::Serialize for TempSer<'a$(, $generic)?> {
+                fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                    super::serialize(self.0, ser)
+                }
+            }
 
             #[allow(clippy::mutable_key_type)]
-            pub fn serialize<$($generic: serde::Serialize, )?$val: serde::Serialize, S: serde::Serializer>(
+            pub fn serialize<$($generic: $($ser)+, )?$val: serde::Serialize, S: serde::Serializer>(
                 val: &$map,
                 ser: S,
             ) -> Result<S::Ok, S::Error> {
@@ -327,12 +352,12 @@ macro_rules! serde_map_key {
                 serde::ser::SerializeMap::end(map)
             }
 
-            struct Visitor<$val: for<'a> serde::Deserialize<'a>$(, $generic: for<'a> serde::Deserialize<'a>)?> {
+            struct Visitor<$val: for<'a> serde::Deserialize<'a>$(, $generic: $($de)+)?> {
                 ph_k: std::marker::PhantomData<$val>,
                 $(ph: std::marker::PhantomData<$generic>,)?
             }
 
-            impl<'de$(, $generic: for<'a> serde::Deserialize<'a>)?, $val: for<'a> serde::Deserialize<'a>> serde::de::Visitor<'de> for Visitor<$val, $($generic)?> {
+            impl<'de$(, $generic: $($de)+)?, $val: for<'a> serde::Deserialize<'a>> serde::de::Visitor<'de> for Visitor<$val, $($generic)?> {
                 type Value = $map;
 
                 fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -345,7 +370,7 @@ macro_rules! serde_map_key {
                 {
 
                     #[allow(clippy::redundant_closure_call, clippy::mutable_key_type)]
-                    let mut ret = $create(map.size_hint().unwrap_or_default());
+                    let mut ret = $create(crate::limits::capacity(map.size_hint()));
                     while let Some((k, val)) = map.next_entry::<TempDe$(<$generic>)?, $val>()? {
                         ret.$insert(k.0, val);
                     }
@@ -356,7 +381,7 @@ macro_rules! serde_map_key {
             pub fn deserialize<'de, D$(, $generic)?, $val: for<'a> serde::Deserialize<'a>>(de: D) -> Result<$map, D::Error>
             where
                 D: serde::Deserializer<'de>,
-                $($generic: for<'a> serde::Deserialize<'a>,)?
+                $($generic: $($de)+,)?
             {
                 de.deserialize_map(Visitor$(::<$val, $generic>)? {
                     ph_k: std::marker::PhantomData::<$val>,
@@ -368,23 +393,35 @@ macro_rules! serde_map_key {
 }
 
 macro_rules! derive_extension_types {
-    ($ty:ty$(, $generic:ident)?) => {
-        serde_option!($ty$(, $generic)?);
-        serde_result!($ty$(, $generic)?);
-        serde_seq!(Vec<$ty>, $ty, Vec::with_capacity, push, vec$(, $generic)?);
+    ($ty:ty) => {
+        derive_extension_types!(@impl $ty);
+    };
+    ($ty:ty, $generic:ident) => {
+        derive_extension_types!(
+            @impl $ty,
+            $generic: [serde::Serialize], [for<'a> serde::Deserialize<'a>]
+        );
+    };
+    ($ty:ty, $generic:ident: $bound:path) => {
+        derive_extension_types!(@impl $ty, $generic: [$bound], [$bound]);
+    };
+    (@impl $ty:ty$(, $generic:ident: [$($ser:tt)+], [$($de:tt)+])?) => {
+        serde_option!($ty$(, $generic: [$($ser)+], [$($de)+])?);
+        serde_result!($ty$(, $generic: [$($ser)+], [$($de)+])?);
+        serde_seq!(Vec<$ty>, $ty, Vec::with_capacity, push, vec$(, $generic: [$($ser)+], [$($de)+])?);
         serde_seq!(
             std::collections::VecDeque<$ty>,
             $ty,
             std::collections::VecDeque::with_capacity,
             push_back,
-            vec_deque$(, $generic)?
+            vec_deque$(, $generic: [$($ser)+], [$($de)+])?
         );
         serde_seq!(
             std::collections::LinkedList<$ty>,
             $ty,
             |_| std::collections::LinkedList::new(),
             push_back,
-            linked_list$(, $generic)?
+            linked_list$(, $generic: [$($ser)+], [$($de)+])?
         );
         serde_map!(
             std::collections::HashMap<K, $ty>,
@@ -393,7 +430,7 @@ macro_rules! derive_extension_types {
             $ty,
             std::collections::HashMap::with_capacity,
             insert,
-            hash_map$(, $generic)?
+            hash_map$(, $generic: [$($ser)+], [$($de)+])?
         );
         serde_map!(
             std::collections::BTreeMap<K, $ty>,
@@ -402,9 +439,9 @@ macro_rules! derive_extension_types {
             $ty,
             |_| std::collections::BTreeMap::new(),
             insert,
-            btree_map$(, $generic)?
+            btree_map$(, $generic: [$($ser)+], [$($de)+])?
         );
-    }
+    };
 }
 
 macro_rules! derive_hash_types {
@@ -498,7 +535,7 @@ macro_rules! deserialize_string {
 }
 
 macro_rules! serde_request_response {
-    ($ty:ty, $name:tt, $head:ty, $borrowed_head:ty) => {
+    ($ty:ty, $name:tt, $head:ty, $borrowed_head:expr) => {
         #[derive(serde::Deserialize)]
         #[serde(field_identifier, rename_all = "lowercase")]
         enum Field {
@@ -509,14 +546,10 @@ macro_rules! serde_request_response {
         impl Field {
             const fn as_str(&self) -> &'static str {
                 match self {
-                    Field::Head => "head",
-                    Field::Body => "body",
+                    Field::Head => crate::head::HEAD_FIELD,
+                    Field::Body => crate::head::BODY_FIELD,
                 }
             }
-
-            const fn len() -> usize {
-                2
-            }
         }
 
         pub fn serialize<S, T>(val: &$ty, ser: S) -> Result<S::Ok, S::Error>
@@ -524,21 +557,15 @@ macro_rules! serde_request_response {
             S: serde::Serializer,
             T: serde::Serialize,
         {
-            if !val.extensions().is_empty() {
-                return Err(serde::ser::Error::custom("extensions is not empty"));
-            }
-            let mut state = ser.serialize_struct(STRUCT_NAME, Field::len())?;
-            serde::ser::SerializeStruct::serialize_field(
-                &mut state,
-                Field::Head.as_str(),
-                &<$borrowed_head>::from(val),
-            )?;
-            serde::ser::SerializeStruct::serialize_field(
-                &mut state,
-                Field::Body.as_str(),
+            let head = $borrowed_head(val);
+            crate::head::serialize_message(
+                $name,
+                &head,
+                head.extensions_len(),
+                val.extensions(),
                 val.body(),
-            )?;
-            serde::ser::SerializeStruct::end(state)
+                ser,
+            )
         }
 
         struct Visitor<T> {
@@ -605,6 +632,7 @@ macro_rules! serde_request_response {
             D: serde::Deserializer<'de>,
         {
             const FIELDS: &[&str] = &[Field::Head.as_str(), Field::Body.as_str()];
+            let _depth = crate::limits::Depth::enter()?;
             de.deserialize_struct(
                 $name,
                 FIELDS,
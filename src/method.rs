@@ -1,9 +1,139 @@
+use std::fmt;
+
+use serde::{de, ser::SerializeTuple, Deserialize, Deserializer, Serializer};
+
 type Type = http::Method;
 const EXPECT_MESSAGE: &str = "valid method name";
 
-serialize_str!(Type);
+// Binary formats write a tag byte followed by an optional string: the index of a standard
+// method with no string, or `EXTENSION_TAG` followed by the extension method.
+const STANDARD: [Type; 9] = [
+    Type::GET,
+    Type::POST,
+    Type::PUT,
+    Type::DELETE,
+    Type::HEAD,
+    Type::OPTIONS,
+    Type::CONNECT,
+    Type::PATCH,
+    Type::TRACE,
+];
+const EXTENSION_TAG: u8 = u8::MAX;
+
+pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
+    if ser.is_human_readable() {
+        return ser.serialize_str(val.as_str());
+    }
+
+    let (tag, extension) = match STANDARD.iter().position(|method| method == val) {
+        Some(tag) => (tag as u8, None),
+        None => (EXTENSION_TAG, Some(val.as_str())),
+    };
+    let mut tuple = ser.serialize_tuple(2)?;
+    tuple.serialize_element(&tag)?;
+    tuple.serialize_element(&extension)?;
+    tuple.end()
+}
+
 create_visitor!(Visitor, Type, EXPECT_MESSAGE, (visit_str, &str));
-deserialize_str!(Visitor, Type);
+
+struct Extension(Type);
+
+impl<'de> Deserialize<'de> for Extension {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_str(Visitor).map(Extension)
+    }
+}
+
+struct CompactVisitor;
+
+impl<'de> de::Visitor<'de> for CompactVisitor {
+    type Value = Type;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(EXPECT_MESSAGE)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let tag: u8 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let extension: Option<Extension> = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        match (tag, extension) {
+            (EXTENSION_TAG, Some(method)) => Ok(method.0),
+            (EXTENSION_TAG, None) => Err(de::Error::custom("extension method without a name")),
+            (tag, None) => STANDARD.get(usize::from(tag)).cloned().ok_or_else(|| {
+                de::Error::invalid_value(de::Unexpected::Unsigned(tag.into()), &self)
+            }),
+            (_, Some(_)) => Err(de::Error::custom("standard method with a name")),
+        }
+    }
+}
+
+pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if de.is_human_readable() {
+        de.deserialize_str(Visitor)
+    } else {
+        de.deserialize_tuple(2, CompactVisitor)
+    }
+}
 
 derive_extension_types!(super::Type);
 derive_hash_types!(super::Type);
+
+/// Same representation as [`method`](super), but standard methods are matched
+/// case-insensitively when deserializing human-readable formats, so `"get"` becomes
+/// [`Method::GET`](http::Method::GET). Extension methods stay case-sensitive.
+pub mod case_insensitive {
+    use std::fmt;
+
+    use serde::{de, Deserializer, Serializer};
+
+    use super::{Type, EXPECT_MESSAGE, STANDARD};
+
+    pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        super::serialize(val, ser)
+    }
+
+    struct Visitor;
+
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = Type;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str(EXPECT_MESSAGE)
+        }
+
+        fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+            match STANDARD
+                .iter()
+                .find(|method| method.as_str().eq_ignore_ascii_case(val))
+            {
+                Some(method) => Ok(method.clone()),
+                None => val.try_into().map_err(de::Error::custom),
+            }
+        }
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if de.is_human_readable() {
+            de.deserialize_str(Visitor)
+        } else {
+            super::deserialize(de)
+        }
+    }
+
+    derive_extension_types!(super::Type);
+    derive_hash_types!(super::Type);
+}
@@ -0,0 +1,133 @@
+use std::{cmp::Ordering, ops::Deref};
+
+use http::{
+    uri::{Authority, PathAndQuery, Scheme},
+    HeaderName, Method, Uri,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A wrapper giving a total order to [`http`] types that don't implement [`Ord`], so they
+/// can be used as the key of a [`BTreeMap`](std::collections::BTreeMap) or in a
+/// [`BTreeSet`](std::collections::BTreeSet).
+///
+/// The order is consistent with each type's [`Eq`]:
+/// - [`Method`], [`HeaderName`] and [`PathAndQuery`] compare lexicographically on `as_str()`
+/// - [`Authority`] and [`Scheme`] compare lexicographically on `as_str()`, ignoring ASCII case
+/// - [`Uri`] compares by scheme, authority, path and query in that order, with a missing
+///   component ordered first
+///
+/// Serializes and deserializes like the wrapped type, e.g. as
+/// [`method`](crate::method) for `Ordered<Method>`.
+///
+/// ```
+/// use std::collections::{BTreeMap, BTreeSet};
+///
+/// use http::{HeaderName, Method};
+/// use http_serde_ext::Ordered;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     timeouts: BTreeMap<Ordered<Method>, u64>,
+///     strip: BTreeSet<Ordered<HeaderName>>,
+/// }
+///
+/// let json = r#"{"timeouts":{"POST":30,"GET":5},"strip":["x-b","x-a"]}"#;
+/// let config: Config = serde_json::from_str(json).unwrap();
+/// assert_eq!(config.timeouts[&Ordered(Method::GET)], 5);
+/// assert_eq!(
+///     serde_json::to_string(&config).unwrap(),
+///     r#"{"timeouts":{"GET":5,"POST":30},"strip":["x-a","x-b"]}"#
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Ordered<T>(pub T);
+
+impl<T> Ordered<T> {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Ordered<T> {
+    fn from(val: T) -> Self {
+        Self(val)
+    }
+}
+
+impl<T> Deref for Ordered<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+fn cmp_ignore_ascii_case(a: &str, b: &str) -> Ordering {
+    let a = a.bytes().map(|b| b.to_ascii_lowercase());
+    let b = b.bytes().map(|b| b.to_ascii_lowercase());
+    a.cmp(b)
+}
+
+fn cmp_option(a: Option<&str>, b: Option<&str>, cmp: fn(&str, &str) -> Ordering) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => cmp(a, b),
+        _ => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+macro_rules! ordered {
+    ($ty:ty, $path:ident, |$a:ident, $b:ident| $cmp:expr) => {
+        impl Ord for Ordered<$ty> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                let ($a, $b) = (&self.0, &other.0);
+                $cmp
+            }
+        }
+
+        impl PartialOrd for Ordered<$ty> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Serialize for Ordered<$ty> {
+            fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                crate::$path::serialize(&self.0, ser)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Ordered<$ty> {
+            fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+                crate::$path::deserialize(de).map(Self)
+            }
+        }
+    };
+}
+
+ordered!(Method, method, |a, b| a.as_str().cmp(b.as_str()));
+ordered!(HeaderName, header_name, |a, b| a.as_str().cmp(b.as_str()));
+ordered!(PathAndQuery, path_and_query, |a, b| a
+    .as_str()
+    .cmp(b.as_str()));
+ordered!(Authority, authority, |a, b| cmp_ignore_ascii_case(
+    a.as_str(),
+    b.as_str()
+));
+ordered!(Scheme, scheme, |a, b| cmp_ignore_ascii_case(
+    a.as_str(),
+    b.as_str()
+));
+ordered!(Uri, uri, |a, b| {
+    cmp_option(a.scheme_str(), b.scheme_str(), cmp_ignore_ascii_case)
+        .then_with(|| {
+            cmp_option(
+                a.authority().map(Authority::as_str),
+                b.authority().map(Authority::as_str),
+                cmp_ignore_ascii_case,
+            )
+        })
+        .then_with(|| a.path().cmp(b.path()))
+        .then_with(|| a.query().cmp(&b.query()))
+});
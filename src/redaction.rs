@@ -0,0 +1,244 @@
+use std::borrow::Cow;
+
+use http::{
+    header::{AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION, SET_COOKIE},
+    HeaderMap, HeaderName, HeaderValue, Request, Response,
+};
+use serde::{Serialize, Serializer};
+
+const PLACEHOLDER: &str = "<redacted>";
+const PLACEHOLDER_PREFIX: &str = "<redacted";
+const DEFAULT_NAMES: [HeaderName; 6] = [
+    AUTHORIZATION,
+    PROXY_AUTHORIZATION,
+    COOKIE,
+    SET_COOKIE,
+    HeaderName::from_static("x-api-key"),
+    HeaderName::from_static("x-auth-token"),
+];
+
+/// Which header values to replace with a placeholder when serializing.
+///
+/// A value is redacted if its name is in the deny list, or if it is
+/// [sensitive](HeaderValue::is_sensitive) and sensitive values are redacted. The built-in deny
+/// list is `authorization`, `proxy-authorization`, `cookie`, `set-cookie`, `x-api-key` and
+/// `x-auth-token`.
+///
+/// Redacted values are written as `<redacted>`. With the `sha2` feature, they are written as
+/// `<redacted sha256:...>` when a hash prefix is set.
+///
+/// The [`header_map::redacted`](crate::header_map::redacted),
+/// [`request::redacted`](crate::request::redacted) and
+/// [`response::redacted`](crate::response::redacted) modules use the default policy. Other
+/// policies serialize through [`header_map`](Self::header_map), [`request`](Self::request)
+/// and [`response`](Self::response). The deserializers of those modules refuse placeholders so
+/// redacted messages can't be replayed by accident.
+///
+/// ```
+/// use http::{HeaderMap, HeaderName, HeaderValue};
+/// use http_serde_ext::RedactionPolicy;
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("authorization", HeaderValue::from_static("Bearer secret"));
+/// headers.insert("x-session", HeaderValue::from_static("abc"));
+/// headers.insert("accept", HeaderValue::from_static("*/*"));
+///
+/// let policy = RedactionPolicy::new().deny(HeaderName::from_static("x-session"));
+/// let json = serde_json::to_string(&policy.header_map(&headers)).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"authorization":"<redacted>","x-session":"<redacted>","accept":"*/*"}"#
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct RedactionPolicy {
+    defaults: bool,
+    names: Vec<HeaderName>,
+    sensitive: bool,
+    #[cfg(feature = "sha2")]
+    hash_prefix: usize,
+}
+
+impl Default for RedactionPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RedactionPolicy {
+    /// Creates a policy redacting the built-in deny list and sensitive values.
+    pub const fn new() -> Self {
+        Self {
+            defaults: true,
+            names: Vec::new(),
+            sensitive: true,
+            #[cfg(feature = "sha2")]
+            hash_prefix: 0,
+        }
+    }
+
+    /// Creates a policy that redacts nothing.
+    pub const fn empty() -> Self {
+        Self {
+            defaults: false,
+            names: Vec::new(),
+            sensitive: false,
+            #[cfg(feature = "sha2")]
+            hash_prefix: 0,
+        }
+    }
+
+    /// Adds `name` to the deny list.
+    pub fn deny(mut self, name: HeaderName) -> Self {
+        self.names.push(name);
+        self
+    }
+
+    /// Sets whether [sensitive](HeaderValue::is_sensitive) values are redacted.
+    pub fn redact_sensitive(mut self, sensitive: bool) -> Self {
+        self.sensitive = sensitive;
+        self
+    }
+
+    /// Sets how many hex digits of the SHA-256 of a value to keep in its placeholder, up
+    /// to `64`. `0` keeps none.
+    ///
+    /// The hash isn't salted, so it only hides values that are hard to guess.
+    ///
+    /// ```
+    /// use http::{HeaderMap, HeaderValue};
+    /// use http_serde_ext::RedactionPolicy;
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("authorization", HeaderValue::from_static("Bearer secret"));
+    ///
+    /// let policy = RedactionPolicy::new().hash_prefix(8);
+    /// let json = serde_json::to_string(&policy.header_map(&headers)).unwrap();
+    /// assert_eq!(json, r#"{"authorization":"<redacted sha256:bffde204>"}"#);
+    /// ```
+    #[cfg(feature = "sha2")]
+    pub fn hash_prefix(mut self, len: usize) -> Self {
+        self.hash_prefix = len.min(64);
+        self
+    }
+
+    /// Returns `true` if the policy redacts `value` of the header `name`.
+    pub fn is_redacted(&self, name: &HeaderName, value: &HeaderValue) -> bool {
+        (self.sensitive && value.is_sensitive())
+            || (self.defaults && DEFAULT_NAMES.contains(name))
+            || self.names.contains(name)
+    }
+
+    /// Returns `true` if `value` is a placeholder written in place of a redacted value.
+    pub fn is_placeholder(value: &HeaderValue) -> bool {
+        let value = value.as_bytes();
+        value.starts_with(PLACEHOLDER_PREFIX.as_bytes()) && value.ends_with(b">")
+    }
+
+    /// Returns a copy of `headers` with every redacted value replaced by its placeholder.
+    pub fn redact(&self, headers: &HeaderMap) -> HeaderMap {
+        let mut redacted = HeaderMap::with_capacity(headers.len());
+        for (name, value) in headers {
+            redacted.append(name, self.redact_value(name, value).into_owned());
+        }
+        redacted
+    }
+
+    // Returns `value`, or its placeholder if it is redacted.
+    pub(crate) fn redact_value<'a>(
+        &self,
+        name: &HeaderName,
+        value: &'a HeaderValue,
+    ) -> Cow<'a, HeaderValue> {
+        if self.is_redacted(name, value) {
+            Cow::Owned(self.placeholder(value))
+        } else {
+            Cow::Borrowed(value)
+        }
+    }
+
+    #[cfg(feature = "sha2")]
+    fn placeholder(&self, value: &HeaderValue) -> HeaderValue {
+        use std::fmt::Write;
+
+        use sha2::{Digest, Sha256};
+
+        if self.hash_prefix == 0 {
+            return HeaderValue::from_static(PLACEHOLDER);
+        }
+
+        let mut hex = String::with_capacity(64);
+        for byte in Sha256::digest(value.as_bytes()) {
+            let _ = write!(hex, "{byte:02x}");
+        }
+        let placeholder = format!("{PLACEHOLDER_PREFIX} sha256:{}>", &hex[..self.hash_prefix]);
+        HeaderValue::from_str(&placeholder).expect("placeholder is a valid header value")
+    }
+
+    #[cfg(not(feature = "sha2"))]
+    fn placeholder(&self, _value: &HeaderValue) -> HeaderValue {
+        HeaderValue::from_static(PLACEHOLDER)
+    }
+
+    /// Returns a [`Serialize`] for `headers` in the [`header_map`](crate::header_map)
+    /// representation, with redacted values replaced.
+    pub fn header_map<'a>(&'a self, headers: &'a HeaderMap) -> impl Serialize + 'a {
+        RedactedHeaderMap {
+            policy: self,
+            headers,
+        }
+    }
+
+    /// Returns a [`Serialize`] for `request` in the [`request`](crate::request)
+    /// representation, with redacted header values replaced.
+    pub fn request<'a, T: Serialize>(&'a self, request: &'a Request<T>) -> impl Serialize + 'a {
+        RedactedRequest {
+            policy: self,
+            request,
+        }
+    }
+
+    /// Returns a [`Serialize`] for `response` in the [`response`](crate::response)
+    /// representation, with redacted header values replaced.
+    pub fn response<'a, T: Serialize>(&'a self, response: &'a Response<T>) -> impl Serialize + 'a {
+        RedactedResponse {
+            policy: self,
+            response,
+        }
+    }
+}
+
+pub(crate) static DEFAULT_POLICY: RedactionPolicy = RedactionPolicy::new();
+
+struct RedactedHeaderMap<'a> {
+    policy: &'a RedactionPolicy,
+    headers: &'a HeaderMap,
+}
+
+impl<'a> Serialize for RedactedHeaderMap<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        crate::header_map::serialize_redacted(self.headers, self.policy, ser)
+    }
+}
+
+struct RedactedRequest<'a, T> {
+    policy: &'a RedactionPolicy,
+    request: &'a Request<T>,
+}
+
+impl<'a, T: Serialize> Serialize for RedactedRequest<'a, T> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        crate::request::serialize_redacted(self.request, self.policy, ser)
+    }
+}
+
+struct RedactedResponse<'a, T> {
+    policy: &'a RedactionPolicy,
+    response: &'a Response<T>,
+}
+
+impl<'a, T: Serialize> Serialize for RedactedResponse<'a, T> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        crate::response::serialize_redacted(self.response, self.policy, ser)
+    }
+}
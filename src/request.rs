@@ -1,8 +1,16 @@
 use std::mem;
 
-use http::{request::Builder, HeaderMap, Method, Uri, Version};
+use http::{request::Builder, Method, Uri, Version};
 use serde::{de, Deserialize, Serialize};
 
+use crate::{
+    head::{deserialize_partial_message, merge_headers, some, BorrowedHeaders, Headers, IntoHeaders},
+    header_map::Entries,
+    limits::Depth,
+    redaction::DEFAULT_POLICY,
+    DuplicatePolicy, RedactionPolicy,
+};
+
 type Type<T> = http::Request<T>;
 const STRUCT_NAME: &str = "Request";
 
@@ -12,37 +20,82 @@ struct BorrowedHead<'a> {
     method: &'a Method,
     #[serde(with = "crate::uri")]
     uri: &'a Uri,
-    #[serde(with = "crate::header_map")]
-    headers: &'a HeaderMap,
+    headers: BorrowedHeaders<'a>,
     #[serde(with = "crate::version")]
     version: Version,
 }
 
+impl<'a> BorrowedHead<'a> {
+    fn extensions_len(&self) -> usize {
+        self.headers.extensions_len()
+    }
+
+    fn with_original_case<T>(val: &'a Type<T>) -> Self {
+        let mut head = Self::from(val);
+        head.headers.case = val.extensions().get();
+        head
+    }
+
+    fn with_sensitive<T>(val: &'a Type<T>) -> Self {
+        let mut head = Self::from(val);
+        head.headers.with_sensitive = true;
+        head
+    }
+
+    fn with_cookies<T>(val: &'a Type<T>) -> Self {
+        let mut head = Self::from(val);
+        head.headers.cookies = true;
+        head
+    }
+
+    fn sorted<T>(val: &'a Type<T>) -> Self {
+        let mut head = Self::from(val);
+        head.headers.sorted = true;
+        head
+    }
+
+    fn redacted<T>(val: &'a Type<T>, policy: &'a RedactionPolicy) -> Self {
+        let mut head = Self::from(val);
+        head.headers.redaction = Some(policy);
+        head
+    }
+
+    fn redacted_default<T>(val: &'a Type<T>) -> Self {
+        Self::redacted(val, &DEFAULT_POLICY)
+    }
+}
+
 impl<'a, T> From<&'a Type<T>> for BorrowedHead<'a> {
     fn from(val: &'a Type<T>) -> Self {
         Self {
             method: val.method(),
             uri: val.uri(),
-            headers: val.headers(),
+            headers: BorrowedHeaders {
+                headers: val.headers(),
+                case: None,
+                with_sensitive: false,
+                redaction: None,
+                cookies: false,
+                sorted: false,
+            },
             version: val.version(),
         }
     }
 }
 
 #[derive(Deserialize)]
-struct Head {
+struct Head<H> {
     #[serde(with = "crate::method")]
     method: Method,
     #[serde(with = "crate::uri")]
     uri: Uri,
-    #[serde(with = "crate::header_map")]
-    headers: HeaderMap,
+    headers: H,
     #[serde(with = "crate::version")]
     version: Version,
 }
 
-impl Head {
-    fn try_into_with_body<T, E>(mut self, body: T) -> Result<Type<T>, E>
+impl<H: IntoHeaders> Head<H> {
+    fn try_into_with_body<T, E>(self, body: T) -> Result<Type<T>, E>
     where
         E: de::Error,
     {
@@ -51,16 +104,224 @@ impl Head {
             .uri(self.uri)
             .version(self.version);
 
-        if let Some(headers) = builder.headers_mut() {
-            mem::swap(&mut self.headers, headers);
+        let (mut headers, case) = self.headers.into_parts();
+        if let Some(builder_headers) = builder.headers_mut() {
+            mem::swap(&mut headers, builder_headers);
         } else {
             return Err(de::Error::custom("builder doesn't have headers"));
         }
 
+        if let Some(case) = case {
+            builder = builder.extension(case);
+        }
+
         builder.body(body).map_err(de::Error::custom)
     }
 }
 
-serde_request_response!(Type<T>, STRUCT_NAME, Head, BorrowedHead);
+serde_request_response!(Type<T>, STRUCT_NAME, Head<Headers>, BorrowedHead::from);
+
+pub(crate) fn serialize_redacted<S, T>(
+    val: &Type<T>,
+    policy: &RedactionPolicy,
+    ser: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: Serialize,
+{
+    let head = BorrowedHead::redacted(val, policy);
+    crate::head::serialize_message(
+        STRUCT_NAME,
+        &head,
+        head.extensions_len(),
+        val.extensions(),
+        val.body(),
+        ser,
+    )
+}
+
+// A head whose fields may be missing in human-readable formats.
+#[derive(Deserialize)]
+struct PartialHead {
+    #[serde(default, deserialize_with = "some::method")]
+    method: Option<Method>,
+    #[serde(default, deserialize_with = "some::uri")]
+    uri: Option<Uri>,
+    #[serde(default, deserialize_with = "some::headers")]
+    headers: Option<Entries>,
+    #[serde(default, deserialize_with = "some::version")]
+    version: Option<Version>,
+}
+
+/// A [`DeserializeSeed`](de::DeserializeSeed) that merges a request in the
+/// [`request`](self) representation onto an existing one.
+///
+/// The method, URI, version and body replace those of the template, and the headers are
+/// merged into its headers as by [`header_map::MergeInto`](crate::header_map::MergeInto). In
+/// human-readable formats, any field of the head, the head itself and the body may be left
+/// out to keep the template's. Extensions of the template are kept.
+///
+/// The [`DuplicatePolicy`] applies both to names already in the template and to names
+/// repeated in the input. The template is left unchanged if the input fails to deserialize,
+/// if a header is a duplicate under [`DuplicatePolicy::Error`] or if the merged headers
+/// exceed the [`Limits`](crate::Limits).
+///
+/// ```
+/// use http::{Method, Request};
+/// use http_serde_ext::{request::MergeInto, DuplicatePolicy};
+/// use serde::de::DeserializeSeed;
+///
+/// let mut request = Request::builder()
+///     .uri("https://example.com/")
+///     .header("user-agent", "app/1.0")
+///     .header("accept", "*/*")
+///     .body(String::new())
+///     .unwrap();
+///
+/// let json = r#"{"head":{"method":"POST","headers":{"user-agent":"app/2.0"}},"body":"hi"}"#;
+/// let mut de = serde_json::Deserializer::from_str(json);
+/// MergeInto::new(&mut request)
+///     .policy(DuplicatePolicy::LastWins)
+///     .deserialize(&mut de)
+///     .unwrap();
+/// assert_eq!(request.method(), Method::POST);
+/// assert_eq!(request.uri(), "https://example.com/");
+/// assert_eq!(request.headers()["user-agent"], "app/2.0");
+/// assert_eq!(request.headers()["accept"], "*/*");
+/// assert_eq!(request.body(), "hi");
+/// ```
+pub struct MergeInto<'a, T> {
+    request: &'a mut Type<T>,
+    policy: DuplicatePolicy,
+}
+
+impl<'a, T> MergeInto<'a, T> {
+    /// Creates a seed merging onto `request`, appending header values.
+    pub fn new(request: &'a mut Type<T>) -> Self {
+        Self {
+            request,
+            policy: DuplicatePolicy::default(),
+        }
+    }
+
+    /// Sets what happens to header names that are already in the template or repeated in
+    /// the input.
+    pub fn policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl<'de, 'a, T> de::DeserializeSeed<'de> for MergeInto<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        let _depth = Depth::enter()?;
+        let (head, body) = deserialize_partial_message::<_, PartialHead, T>(STRUCT_NAME, de)?;
+        if let Some(head) = head {
+            if let Some(headers) = head.headers {
+                merge_headers(self.request.headers_mut(), headers, self.policy)?;
+            }
+            if let Some(method) = head.method {
+                *self.request.method_mut() = method;
+            }
+            if let Some(uri) = head.uri {
+                *self.request.uri_mut() = uri;
+            }
+            if let Some(version) = head.version {
+                *self.request.version_mut() = version;
+            }
+        }
+        if let Some(body) = body {
+            *self.request.body_mut() = body;
+        }
+        Ok(())
+    }
+}
 
 derive_extension_types!(super::Type<T>, T);
+
+/// Same representation as [`request`](super), but header names are written with the
+/// spelling recorded in a [`HeaderCaseMap`](crate::HeaderCaseMap) extension.
+///
+/// Deserializing records the spelling of every header name and adds the
+/// [`HeaderCaseMap`](crate::HeaderCaseMap) extension if any name wasn't lowercase.
+pub mod original_case {
+    use super::{BorrowedHead, Head, Type, STRUCT_NAME};
+    use crate::head::CasedHeaders;
+
+    serde_request_response!(
+        Type<T>,
+        STRUCT_NAME,
+        Head<CasedHeaders>,
+        BorrowedHead::with_original_case
+    );
+
+    derive_extension_types!(super::Type<T>, T);
+}
+
+/// Same representation as [`request`](super), but sensitive header values are written as by
+/// [`header_map::with_sensitive`](crate::header_map::with_sensitive).
+pub mod with_sensitive {
+    use super::{BorrowedHead, Head, Type, STRUCT_NAME};
+    use crate::head::Headers;
+
+    serde_request_response!(
+        Type<T>,
+        STRUCT_NAME,
+        Head<Headers>,
+        BorrowedHead::with_sensitive
+    );
+
+    derive_extension_types!(super::Type<T>, T);
+}
+
+/// Same representation as [`request`](super), but header values are redacted by the default
+/// [`RedactionPolicy`].
+///
+/// Deserializing fails if a header value is a redaction placeholder.
+pub mod redacted {
+    use super::{BorrowedHead, Head, Type, STRUCT_NAME};
+    use crate::head::RedactedHeaders;
+
+    serde_request_response!(
+        Type<T>,
+        STRUCT_NAME,
+        Head<RedactedHeaders>,
+        BorrowedHead::redacted_default
+    );
+
+    derive_extension_types!(super::Type<T>, T);
+}
+
+/// Same representation as [`request`](super), but `Cookie` and `Set-Cookie` header values are
+/// written as by [`header_map::cookies`](crate::header_map::cookies).
+pub mod cookies {
+    use super::{BorrowedHead, Head, Type, STRUCT_NAME};
+    use crate::head::CookieHeaders;
+
+    serde_request_response!(
+        Type<T>,
+        STRUCT_NAME,
+        Head<CookieHeaders>,
+        BorrowedHead::with_cookies
+    );
+
+    derive_extension_types!(super::Type<T>, T);
+}
+
+/// Same representation as [`request`](super), but header names are written in sorted order as
+/// by [`header_map::sorted`](crate::header_map::sorted), so equal requests serialize to
+/// identical bytes.
+pub mod sorted {
+    use super::{BorrowedHead, Head, Type, STRUCT_NAME};
+    use crate::head::Headers;
+
+    serde_request_response!(Type<T>, STRUCT_NAME, Head<Headers>, BorrowedHead::sorted);
+
+    derive_extension_types!(super::Type<T>, T);
+}
@@ -1,58 +1,452 @@
 use std::mem;
 
-use http::{response::Builder, HeaderMap, StatusCode, Version};
-use serde::{de, Deserialize, Serialize};
+use http::{response::Builder, StatusCode, Version};
+use serde::{de, ser::SerializeStruct, Deserialize, Serialize, Serializer};
+
+use crate::{
+    head::{deserialize_partial_message, merge_headers, some, BorrowedHeaders, Headers, IntoHeaders},
+    header_map::Entries,
+    limits::Depth,
+    redaction::DEFAULT_POLICY,
+    DuplicatePolicy, RedactionPolicy,
+};
 
 type Type<T> = http::Response<T>;
 const STRUCT_NAME: &str = "Response";
+const HEAD_STRUCT_NAME: &str = "Head";
+
+#[derive(Serialize)]
+struct BorrowedStatus(#[serde(with = "crate::status_code")] StatusCode);
 
 #[derive(Serialize)]
+struct BorrowedVersion(#[serde(with = "crate::version")] Version);
+
 struct BorrowedHead<'a> {
-    #[serde(with = "crate::status_code")]
     status: StatusCode,
-    #[serde(with = "crate::header_map")]
-    headers: &'a HeaderMap,
-    #[serde(with = "crate::version")]
+    headers: BorrowedHeaders<'a>,
     version: Version,
+    reason: Option<reason_phrase::BorrowedReasonPhrase<'a>>,
+}
+
+impl<'a> BorrowedHead<'a> {
+    fn extensions_len(&self) -> usize {
+        self.headers.extensions_len() + usize::from(self.reason.is_some())
+    }
+
+    fn with_original_case<T>(val: &'a Type<T>) -> Self {
+        let mut head = Self::from(val);
+        head.headers.case = val.extensions().get();
+        head
+    }
+
+    fn with_sensitive<T>(val: &'a Type<T>) -> Self {
+        let mut head = Self::from(val);
+        head.headers.with_sensitive = true;
+        head
+    }
+
+    fn with_cookies<T>(val: &'a Type<T>) -> Self {
+        let mut head = Self::from(val);
+        head.headers.cookies = true;
+        head
+    }
+
+    fn sorted<T>(val: &'a Type<T>) -> Self {
+        let mut head = Self::from(val);
+        head.headers.sorted = true;
+        head
+    }
+
+    fn redacted<T>(val: &'a Type<T>, policy: &'a RedactionPolicy) -> Self {
+        let mut head = Self::from(val);
+        head.headers.redaction = Some(policy);
+        head
+    }
+
+    fn redacted_default<T>(val: &'a Type<T>) -> Self {
+        Self::redacted(val, &DEFAULT_POLICY)
+    }
 }
 
 impl<'a, T> From<&'a Type<T>> for BorrowedHead<'a> {
     fn from(val: &'a Type<T>) -> Self {
         Self {
             status: val.status(),
-            headers: val.headers(),
+            headers: BorrowedHeaders {
+                headers: val.headers(),
+                case: None,
+                with_sensitive: false,
+                redaction: None,
+                cookies: false,
+                sorted: false,
+            },
             version: val.version(),
+            #[cfg(feature = "hyper")]
+            reason: val
+                .extensions()
+                .get::<hyper::ext::ReasonPhrase>()
+                .map(|reason| reason_phrase::BorrowedReasonPhrase(reason.as_bytes())),
+            #[cfg(not(feature = "hyper"))]
+            reason: None,
         }
     }
 }
 
+impl<'a> Serialize for BorrowedHead<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        // The reason phrase is only written to human-readable formats when present,
+        // binary formats need every field to be in place, with or without the `hyper`
+        // feature.
+        let with_reason = self.reason.is_some() || !ser.is_human_readable();
+
+        let mut state = ser.serialize_struct(HEAD_STRUCT_NAME, 3 + usize::from(with_reason))?;
+        state.serialize_field("status", &BorrowedStatus(self.status))?;
+        state.serialize_field("headers", &self.headers)?;
+        state.serialize_field("version", &BorrowedVersion(self.version))?;
+        if with_reason {
+            state.serialize_field("reason", &self.reason)?;
+        }
+        state.end()
+    }
+}
+
 #[derive(Deserialize)]
-struct Head {
+struct Head<H> {
     #[serde(with = "crate::status_code")]
     status: StatusCode,
-    #[serde(with = "crate::header_map")]
-    headers: HeaderMap,
+    headers: H,
     #[serde(with = "crate::version")]
     version: Version,
+    #[serde(default)]
+    reason: Option<reason_phrase::ReasonPhrase>,
 }
 
-impl Head {
-    fn try_into_with_body<T, E>(mut self, body: T) -> Result<Type<T>, E>
+impl<H: IntoHeaders> Head<H> {
+    fn try_into_with_body<T, E>(self, body: T) -> Result<Type<T>, E>
     where
         E: de::Error,
     {
         let mut builder = Builder::new().status(self.status).version(self.version);
 
-        if let Some(headers) = builder.headers_mut() {
-            mem::swap(&mut self.headers, headers);
+        let (mut headers, case) = self.headers.into_parts();
+        if let Some(builder_headers) = builder.headers_mut() {
+            mem::swap(&mut headers, builder_headers);
         } else {
             return Err(de::Error::custom("builder doesn't have headers"));
         }
 
+        if let Some(case) = case {
+            builder = builder.extension(case);
+        }
+
+        if let (Some(reason), Some(extensions)) = (self.reason, builder.extensions_mut()) {
+            reason.insert_into(extensions);
+        }
+
         builder.body(body).map_err(de::Error::custom)
     }
 }
 
-serde_request_response!(Type<T>, STRUCT_NAME, Head, BorrowedHead);
+// The `reason` field of the head. It holds a `hyper::ext::ReasonPhrase` extension with the
+// `hyper` feature, and is read and dropped without it.
+mod reason_phrase {
+    use std::fmt;
+
+    use http::Extensions;
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    const EXPECT_MESSAGE: &str = "a reason phrase";
+
+    pub(super) struct BorrowedReasonPhrase<'a>(pub(super) &'a [u8]);
+
+    impl<'a> Serialize for BorrowedReasonPhrase<'a> {
+        fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            if ser.is_human_readable() {
+                use ser::Error;
+                let val = std::str::from_utf8(self.0).map_err(Error::custom)?;
+                ser.serialize_str(val)
+            } else {
+                ser.serialize_bytes(self.0)
+            }
+        }
+    }
+
+    #[cfg(feature = "hyper")]
+    pub(super) struct ReasonPhrase(hyper::ext::ReasonPhrase);
+
+    #[cfg(not(feature = "hyper"))]
+    pub(super) struct ReasonPhrase;
+
+    impl ReasonPhrase {
+        #[cfg(feature = "hyper")]
+        fn new<E: de::Error, B>(val: B) -> Result<Self, E>
+        where
+            hyper::ext::ReasonPhrase: TryFrom<B>,
+            <hyper::ext::ReasonPhrase as TryFrom<B>>::Error: fmt::Display,
+        {
+            val.try_into().map(Self).map_err(de::Error::custom)
+        }
+
+        #[cfg(not(feature = "hyper"))]
+        fn new<E: de::Error, B>(_val: B) -> Result<Self, E> {
+            Ok(Self)
+        }
+
+        #[cfg(feature = "hyper")]
+        pub(super) fn insert_into(self, extensions: &mut Extensions) {
+            extensions.insert(self.0);
+        }
+
+        #[cfg(not(feature = "hyper"))]
+        pub(super) fn insert_into(self, _extensions: &mut Extensions) {}
+
+        // Drops a reason that may no longer match the status.
+        #[cfg(feature = "hyper")]
+        pub(super) fn remove_from(extensions: &mut Extensions) {
+            extensions.remove::<hyper::ext::ReasonPhrase>();
+        }
+
+        #[cfg(not(feature = "hyper"))]
+        pub(super) fn remove_from(_extensions: &mut Extensions) {}
+    }
+
+    struct Visitor;
+
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = ReasonPhrase;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str(EXPECT_MESSAGE)
+        }
+
+        fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+            self.visit_bytes(val.as_bytes())
+        }
+
+        fn visit_string<E: de::Error>(self, val: String) -> Result<Self::Value, E> {
+            ReasonPhrase::new(val)
+        }
+
+        fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+            ReasonPhrase::new(val)
+        }
+
+        fn visit_byte_buf<E: de::Error>(self, val: Vec<u8>) -> Result<Self::Value, E> {
+            ReasonPhrase::new(val)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ReasonPhrase {
+        fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+            if de.is_human_readable() {
+                de.deserialize_string(Visitor)
+            } else {
+                de.deserialize_byte_buf(Visitor)
+            }
+        }
+    }
+}
+
+serde_request_response!(Type<T>, STRUCT_NAME, Head<Headers>, BorrowedHead::from);
+
+// A head whose fields may be missing in human-readable formats.
+#[derive(Deserialize)]
+struct PartialHead {
+    #[serde(default, deserialize_with = "some::status")]
+    status: Option<StatusCode>,
+    #[serde(default, deserialize_with = "some::headers")]
+    headers: Option<Entries>,
+    #[serde(default, deserialize_with = "some::version")]
+    version: Option<Version>,
+    #[serde(default)]
+    reason: Option<reason_phrase::ReasonPhrase>,
+}
+
+/// A [`DeserializeSeed`](de::DeserializeSeed) that merges a response in the
+/// [`response`](self) representation onto an existing one.
+///
+/// The status, version and body replace those of the template, and the headers are merged
+/// into its headers as by [`header_map::MergeInto`](crate::header_map::MergeInto). In
+/// human-readable formats, any field of the head, the head itself and the body may be left
+/// out to keep the template's. Extensions of the template are kept, except for a reason
+/// phrase when the input sets the status without one.
+///
+/// The [`DuplicatePolicy`] applies both to names already in the template and to names
+/// repeated in the input. The template is left unchanged if the input fails to deserialize,
+/// if a header is a duplicate under [`DuplicatePolicy::Error`] or if the merged headers
+/// exceed the [`Limits`](crate::Limits).
+///
+/// ```
+/// use http::{Response, StatusCode};
+/// use http_serde_ext::response::MergeInto;
+/// use serde::de::DeserializeSeed;
+///
+/// let mut response = Response::builder()
+///     .header("cache-control", "no-store")
+///     .body(String::from("template"))
+///     .unwrap();
+///
+/// let json = r#"{"head":{"status":404,"headers":{"cache-control":"private"}}}"#;
+/// let mut de = serde_json::Deserializer::from_str(json);
+/// MergeInto::new(&mut response).deserialize(&mut de).unwrap();
+/// assert_eq!(response.status(), StatusCode::NOT_FOUND);
+/// assert_eq!(response.headers().get_all("cache-control").iter().count(), 2);
+/// assert_eq!(response.body(), "template");
+/// ```
+pub struct MergeInto<'a, T> {
+    response: &'a mut Type<T>,
+    policy: DuplicatePolicy,
+}
+
+impl<'a, T> MergeInto<'a, T> {
+    /// Creates a seed merging onto `response`, appending header values.
+    pub fn new(response: &'a mut Type<T>) -> Self {
+        Self {
+            response,
+            policy: DuplicatePolicy::default(),
+        }
+    }
+
+    /// Sets what happens to header names that are already in the template or repeated in
+    /// the input.
+    pub fn policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl<'de, 'a, T> de::DeserializeSeed<'de> for MergeInto<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        let _depth = Depth::enter()?;
+        let (head, body) = deserialize_partial_message::<_, PartialHead, T>(STRUCT_NAME, de)?;
+        if let Some(head) = head {
+            if let Some(headers) = head.headers {
+                merge_headers(self.response.headers_mut(), headers, self.policy)?;
+            }
+            let extensions = self.response.extensions_mut();
+            match head.reason {
+                Some(reason) => reason.insert_into(extensions),
+                None if head.status.is_some() => reason_phrase::ReasonPhrase::remove_from(extensions),
+                None => {}
+            }
+            if let Some(status) = head.status {
+                *self.response.status_mut() = status;
+            }
+            if let Some(version) = head.version {
+                *self.response.version_mut() = version;
+            }
+        }
+        if let Some(body) = body {
+            *self.response.body_mut() = body;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn serialize_redacted<S, T>(
+    val: &Type<T>,
+    policy: &RedactionPolicy,
+    ser: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: Serialize,
+{
+    let head = BorrowedHead::redacted(val, policy);
+    crate::head::serialize_message(
+        STRUCT_NAME,
+        &head,
+        head.extensions_len(),
+        val.extensions(),
+        val.body(),
+        ser,
+    )
+}
 
 derive_extension_types!(super::Type<T>, T);
+
+/// Same representation as [`response`](super), but header names are written with the
+/// spelling recorded in a [`HeaderCaseMap`](crate::HeaderCaseMap) extension.
+///
+/// Deserializing records the spelling of every header name and adds the
+/// [`HeaderCaseMap`](crate::HeaderCaseMap) extension if any name wasn't lowercase.
+pub mod original_case {
+    use super::{BorrowedHead, Head, Type, STRUCT_NAME};
+    use crate::head::CasedHeaders;
+
+    serde_request_response!(
+        Type<T>,
+        STRUCT_NAME,
+        Head<CasedHeaders>,
+        BorrowedHead::with_original_case
+    );
+
+    derive_extension_types!(super::Type<T>, T);
+}
+
+/// Same representation as [`response`](super), but sensitive header values are written as by
+/// [`header_map::with_sensitive`](crate::header_map::with_sensitive).
+pub mod with_sensitive {
+    use super::{BorrowedHead, Head, Type, STRUCT_NAME};
+    use crate::head::Headers;
+
+    serde_request_response!(
+        Type<T>,
+        STRUCT_NAME,
+        Head<Headers>,
+        BorrowedHead::with_sensitive
+    );
+
+    derive_extension_types!(super::Type<T>, T);
+}
+
+/// Same representation as [`response`](super), but header values are redacted by the default
+/// [`RedactionPolicy`].
+///
+/// Deserializing fails if a header value is a redaction placeholder.
+pub mod redacted {
+    use super::{BorrowedHead, Head, Type, STRUCT_NAME};
+    use crate::head::RedactedHeaders;
+
+    serde_request_response!(
+        Type<T>,
+        STRUCT_NAME,
+        Head<RedactedHeaders>,
+        BorrowedHead::redacted_default
+    );
+
+    derive_extension_types!(super::Type<T>, T);
+}
+
+/// Same representation as [`response`](super), but `Cookie` and `Set-Cookie` header values are
+/// written as by [`header_map::cookies`](crate::header_map::cookies).
+pub mod cookies {
+    use super::{BorrowedHead, Head, Type, STRUCT_NAME};
+    use crate::head::CookieHeaders;
+
+    serde_request_response!(
+        Type<T>,
+        STRUCT_NAME,
+        Head<CookieHeaders>,
+        BorrowedHead::with_cookies
+    );
+
+    derive_extension_types!(super::Type<T>, T);
+}
+
+/// Same representation as [`response`](super), but header names are written in sorted order as
+/// by [`header_map::sorted`](crate::header_map::sorted), so equal responses serialize to
+/// identical bytes.
+pub mod sorted {
+    use super::{BorrowedHead, Head, Type, STRUCT_NAME};
+    use crate::head::Headers;
+
+    serde_request_response!(Type<T>, STRUCT_NAME, Head<Headers>, BorrowedHead::sorted);
+
+    derive_extension_types!(super::Type<T>, T);
+}
@@ -1,15 +1,88 @@
 use std::fmt;
 
-use serde::{de, Deserializer, Serializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 type Type = http::StatusCode;
 const EXPECT_MESSAGE: &str = "a status code";
 
+#[derive(Serialize)]
+#[serde(rename = "StatusCode")]
+struct BorrowedObject<'a> {
+    code: u16,
+    reason: Option<&'a str>,
+    class: &'a str,
+}
+
+impl<'a> From<&'a Type> for BorrowedObject<'a> {
+    fn from(val: &'a Type) -> Self {
+        Self {
+            code: val.as_u16(),
+            reason: val.canonical_reason(),
+            class: class(val),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "StatusCode")]
+struct Object {
+    code: u16,
+    #[serde(default)]
+    reason: Option<String>,
+    // Empty when missing from the input.
+    #[serde(default)]
+    class: String,
+}
+
+impl Object {
+    // Checks `reason` and `class`, when present, against `code`.
+    fn into_status<E: de::Error>(self) -> Result<Type, E> {
+        let status = Type::from_u16(self.code).map_err(de::Error::custom)?;
+        if let Some(reason) = self.reason {
+            check_reason(&status, &reason)?;
+        }
+        if !self.class.is_empty() && self.class != class(&status) {
+            return Err(de::Error::custom(format!(
+                "class {:?} does not match status code {}",
+                self.class, self.code
+            )));
+        }
+        Ok(status)
+    }
+}
+
+fn check_reason<E: de::Error>(status: &Type, reason: &str) -> Result<(), E> {
+    if status.canonical_reason() == Some(reason) {
+        Ok(())
+    } else {
+        Err(de::Error::custom(format!(
+            "reason {reason:?} does not match status code {}",
+            status.as_u16()
+        )))
+    }
+}
+
+fn class(val: &Type) -> &'static str {
+    if val.is_informational() {
+        "informational"
+    } else if val.is_success() {
+        "success"
+    } else if val.is_redirection() {
+        "redirection"
+    } else if val.is_client_error() {
+        "client_error"
+    } else if val.is_server_error() {
+        "server_error"
+    } else {
+        "unknown"
+    }
+}
+
 pub fn serialize<S: Serializer>(status: &Type, ser: S) -> Result<S::Ok, S::Error> {
     ser.serialize_u16(status.as_u16())
 }
 
-struct Visitor;
+pub(crate) struct Visitor;
 
 impl<'de> de::Visitor<'de> for Visitor {
     type Value = Type;
@@ -18,32 +91,49 @@ impl<'de> de::Visitor<'de> for Visitor {
         formatter.write_str(EXPECT_MESSAGE)
     }
 
-    fn visit_i32<E: de::Error>(self, val: i32) -> Result<Self::Value, E> {
-        self.visit_u16(val as u16)
+    fn visit_i64<E: de::Error>(self, val: i64) -> Result<Self::Value, E> {
+        let val = u16::try_from(val)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(val), &self))?;
+        self.visit_u16(val)
     }
 
-    fn visit_i16<E: de::Error>(self, val: i16) -> Result<Self::Value, E> {
-        self.visit_u16(val as u16)
+    fn visit_u64<E: de::Error>(self, val: u64) -> Result<Self::Value, E> {
+        let val = u16::try_from(val)
+            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(val), &self))?;
+        self.visit_u16(val)
     }
 
-    fn visit_u8<E: de::Error>(self, val: u8) -> Result<Self::Value, E> {
-        self.visit_u16(val as u16)
+    fn visit_u16<E: de::Error>(self, val: u16) -> Result<Self::Value, E> {
+        val.try_into().map_err(de::Error::custom)
     }
 
-    fn visit_u32<E: de::Error>(self, val: u32) -> Result<Self::Value, E> {
-        self.visit_u16(val as u16)
+    // Accepts either a bare code (`"404"`) or a status line (`"404 Not Found"`), whose reason
+    // phrase must be the canonical one.
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        let (code, reason) = match val.split_once(' ') {
+            Some((code, reason)) => (code, Some(reason)),
+            None => (val, None),
+        };
+        let status = Type::from_bytes(code.as_bytes())
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(val), &self))?;
+        if let Some(reason) = reason {
+            check_reason(&status, reason)?;
+        }
+        Ok(status)
     }
 
-    fn visit_i64<E: de::Error>(self, val: i64) -> Result<Self::Value, E> {
-        self.visit_u16(val as u16)
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        match std::str::from_utf8(val) {
+            Ok(val) => self.visit_str(val),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(val), &self)),
+        }
     }
 
-    fn visit_u64<E: de::Error>(self, val: u64) -> Result<Self::Value, E> {
-        self.visit_u16(val as u16)
-    }
-
-    fn visit_u16<E: de::Error>(self, val: u16) -> Result<Self::Value, E> {
-        val.try_into().map_err(de::Error::custom)
+    fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        Object::deserialize(de::value::MapAccessDeserializer::new(map))?.into_status()
     }
 }
 
@@ -51,9 +141,93 @@ pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
 where
     D: Deserializer<'de>,
 {
-    de.deserialize_u16(Visitor)
+    if de.is_human_readable() {
+        de.deserialize_any(Visitor)
+    } else {
+        de.deserialize_u16(Visitor)
+    }
 }
 
 derive_extension_types!(super::Type);
 derive_hash_types!(super::Type);
 derive_ord_types!(super::Type);
+
+/// Serializes a [`StatusCode`](http::StatusCode) as a status line such as `"404 Not Found"`.
+///
+/// Codes without a canonical reason phrase are written as the bare code, e.g. `"299"`.
+/// Deserializing accepts the same shapes as [`status_code`](super), and fails if a status line's
+/// reason phrase isn't the canonical one for its code.
+pub mod with_reason {
+    use serde::{Deserializer, Serializer};
+
+    use super::{Type, Visitor};
+
+    pub fn serialize<S: Serializer>(status: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        match status.canonical_reason() {
+            Some(reason) => ser.collect_str(&format_args!("{} {reason}", status.as_str())),
+            None => ser.serialize_str(status.as_str()),
+        }
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if de.is_human_readable() {
+            de.deserialize_any(Visitor)
+        } else {
+            de.deserialize_str(Visitor)
+        }
+    }
+
+    derive_extension_types!(super::Type);
+    derive_hash_types!(super::Type);
+    derive_ord_types!(super::Type);
+}
+
+/// Serializes a [`StatusCode`](http::StatusCode) as an object such as
+/// `{"code":404,"reason":"Not Found","class":"client_error"}`.
+///
+/// `reason` is `null` for codes without a canonical reason phrase. `class` is one of
+/// `informational`, `success`, `redirection`, `client_error`, `server_error` or `unknown`.
+/// Deserializing accepts the same shapes as [`status_code`](super), and fails if an object's
+/// `reason` or `class` doesn't match its `code`.
+///
+/// Objects can't be map keys, so unlike [`status_code`](super) this module has no
+/// `hash_map_key` or `btree_map_key`.
+pub mod object {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{BorrowedObject, Object, Type, Visitor};
+
+    pub fn serialize<S: Serializer>(status: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        BorrowedObject::from(status).serialize(ser)
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if de.is_human_readable() {
+            de.deserialize_any(Visitor)
+        } else {
+            Object::deserialize(de)?.into_status()
+        }
+    }
+
+    derive_extension_types!(super::Type);
+    serde_seq!(
+        std::collections::HashSet<super::Type>,
+        super::Type,
+        std::collections::HashSet::with_capacity,
+        insert,
+        hash_set
+    );
+    serde_seq!(
+        std::collections::BTreeSet<super::Type>,
+        super::Type,
+        |_| std::collections::BTreeSet::new(),
+        insert,
+        btree_set
+    );
+}
@@ -0,0 +1,323 @@
+use std::{fmt, ops::RangeInclusive};
+
+use http::StatusCode;
+use serde::{de, ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+
+const EXPECT_MESSAGE: &str = "a status code, class or range";
+const MIN: u16 = 100;
+const MAX: u16 = 999;
+const WORDS: usize = (MAX - MIN) as usize / 64 + 1;
+
+/// A set of [`StatusCode`]s matched by single codes, classes and ranges.
+///
+/// Human-readable formats use a list where every entry is one of:
+/// - a single code, either as a number (`429`) or a string (`"429"`, `"429 Too Many Requests"`)
+/// - a class such as `"5xx"`, matching `500` through `599`
+/// - an inclusive range such as `"500-504"`
+///
+/// A single entry is also accepted in place of the list. Serializing writes the most compact
+/// form: whole classes as `"Nxx"`, lone codes as numbers and everything else as ranges.
+/// Binary formats use a list of inclusive `(start, end)` pairs.
+///
+/// ```
+/// use http::StatusCode;
+/// use http_serde_ext::StatusCodeSet;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     retry_on: StatusCodeSet,
+/// }
+///
+/// let config: Config =
+///     serde_json::from_str(r#"{"retry_on": ["5xx", 429, "408-409"]}"#).unwrap();
+/// assert!(config.retry_on.contains(StatusCode::SERVICE_UNAVAILABLE));
+/// assert!(config.retry_on.contains(StatusCode::TOO_MANY_REQUESTS));
+/// assert!(!config.retry_on.contains(StatusCode::NOT_FOUND));
+///
+/// let json = serde_json::to_string(&config.retry_on).unwrap();
+/// assert_eq!(json, r#"["408-409",429,"5xx"]"#);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct StatusCodeSet {
+    bits: [u64; WORDS],
+}
+
+impl StatusCodeSet {
+    /// Creates an empty set.
+    pub const fn new() -> Self {
+        Self { bits: [0; WORDS] }
+    }
+
+    /// Returns `true` if the set matches `status`.
+    pub fn contains(&self, status: StatusCode) -> bool {
+        let index = Self::index(status.as_u16());
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Returns `true` if the set matches no status code.
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|word| *word == 0)
+    }
+
+    /// Adds a single status code to the set.
+    pub fn insert(&mut self, status: StatusCode) {
+        self.insert_codes(status.as_u16(), status.as_u16());
+    }
+
+    /// Adds every status code from `start` through `end` to the set.
+    pub fn insert_range(&mut self, range: RangeInclusive<StatusCode>) {
+        self.insert_codes(range.start().as_u16(), range.end().as_u16());
+    }
+
+    /// Adds a whole class to the set, e.g. `5` for `500` through `599`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `class` is not between `1` and `9`.
+    pub fn insert_class(&mut self, class: u8) {
+        assert!((1..=9).contains(&class), "invalid status code class");
+        self.insert_entry(Entry::Class(class.into()));
+    }
+
+    /// Iterates over the matched status codes in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = StatusCode> + '_ {
+        (MIN..=MAX)
+            .filter(|code| {
+                let index = Self::index(*code);
+                self.bits[index / 64] & (1 << (index % 64)) != 0
+            })
+            .filter_map(|code| StatusCode::from_u16(code).ok())
+    }
+
+    fn index(code: u16) -> usize {
+        usize::from(code - MIN)
+    }
+
+    fn insert_codes(&mut self, start: u16, end: u16) {
+        for code in start..=end {
+            let index = Self::index(code);
+            self.bits[index / 64] |= 1 << (index % 64);
+        }
+    }
+
+    fn insert_entry(&mut self, entry: Entry) {
+        match entry {
+            Entry::Code(code) => self.insert_codes(code, code),
+            Entry::Class(class) => self.insert_codes(class * 100, class * 100 + 99),
+            Entry::Range(start, end) => self.insert_codes(start, end),
+        }
+    }
+
+    // Maximal runs of consecutive matched codes as inclusive `(start, end)` pairs.
+    fn runs(&self) -> Vec<(u16, u16)> {
+        let mut runs: Vec<(u16, u16)> = Vec::new();
+        for code in self.iter().map(|status| status.as_u16()) {
+            match runs.last_mut() {
+                Some((_, end)) if *end + 1 == code => *end = code,
+                _ => runs.push((code, code)),
+            }
+        }
+        runs
+    }
+}
+
+impl fmt::Debug for StatusCodeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.runs().iter().map(|(start, end)| start..=end))
+            .finish()
+    }
+}
+
+impl FromIterator<StatusCode> for StatusCodeSet {
+    fn from_iter<I: IntoIterator<Item = StatusCode>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<StatusCode> for StatusCodeSet {
+    fn extend<I: IntoIterator<Item = StatusCode>>(&mut self, iter: I) {
+        for status in iter {
+            self.insert(status);
+        }
+    }
+}
+
+enum Entry {
+    Code(u16),
+    Class(u16),
+    Range(u16, u16),
+}
+
+impl Serialize for Entry {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Entry::Code(code) => ser.serialize_u16(code),
+            Entry::Class(class) => ser.collect_str(&format_args!("{class}xx")),
+            Entry::Range(start, end) => ser.collect_str(&format_args!("{start}-{end}")),
+        }
+    }
+}
+
+impl Serialize for StatusCodeSet {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let runs = self.runs();
+        if !ser.is_human_readable() {
+            return ser.collect_seq(runs);
+        }
+
+        let mut entries = Vec::with_capacity(runs.len());
+        for (start, end) in runs {
+            if start % 100 == 0 && end % 100 == 99 {
+                entries.extend((start / 100..=end / 100).map(Entry::Class));
+            } else if start == end {
+                entries.push(Entry::Code(start));
+            } else {
+                entries.push(Entry::Range(start, end));
+            }
+        }
+
+        let mut seq = ser.serialize_seq(Some(entries.len()))?;
+        for entry in &entries {
+            seq.serialize_element(entry)?;
+        }
+        seq.end()
+    }
+}
+
+struct EntryVisitor;
+
+impl EntryVisitor {
+    fn code<E: de::Error>(val: &str) -> Result<u16, E> {
+        de::Visitor::visit_str(crate::status_code::Visitor, val).map(|status| status.as_u16())
+    }
+}
+
+impl<'de> de::Visitor<'de> for EntryVisitor {
+    type Value = Entry;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(EXPECT_MESSAGE)
+    }
+
+    fn visit_i64<E: de::Error>(self, val: i64) -> Result<Self::Value, E> {
+        de::Visitor::visit_i64(crate::status_code::Visitor, val).map(|s| Entry::Code(s.as_u16()))
+    }
+
+    fn visit_u64<E: de::Error>(self, val: u64) -> Result<Self::Value, E> {
+        de::Visitor::visit_u64(crate::status_code::Visitor, val).map(|s| Entry::Code(s.as_u16()))
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        let invalid = || E::invalid_value(de::Unexpected::Str(val), &self);
+
+        if let Some(class) = val.strip_suffix("xx").or_else(|| val.strip_suffix("XX")) {
+            return match class.as_bytes() {
+                [digit @ b'1'..=b'9'] => Ok(Entry::Class(u16::from(digit - b'0'))),
+                _ => Err(invalid()),
+            };
+        }
+
+        // Only digits around the hyphen make a range, so status lines such as
+        // `207 Multi-Status` are read as a code.
+        let is_code = |val: &str| !val.is_empty() && val.bytes().all(|b| b.is_ascii_digit());
+        let range = val
+            .split_once('-')
+            .map(|(start, end)| (start.trim(), end.trim()))
+            .filter(|(start, end)| is_code(start) && is_code(end));
+        if let Some((start, end)) = range {
+            let start = Self::code::<E>(start).map_err(|_| invalid())?;
+            let end = Self::code::<E>(end).map_err(|_| invalid())?;
+            if start > end {
+                return Err(invalid());
+            }
+            return Ok(Entry::Range(start, end));
+        }
+
+        Self::code(val).map(Entry::Code)
+    }
+}
+
+impl<'de> Deserialize<'de> for Entry {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_any(EntryVisitor)
+    }
+}
+
+struct Visitor;
+
+impl<'de> de::Visitor<'de> for Visitor {
+    type Value = StatusCodeSet;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of status codes, classes or ranges")
+    }
+
+    fn visit_i64<E: de::Error>(self, val: i64) -> Result<Self::Value, E> {
+        let mut set = StatusCodeSet::new();
+        set.insert_entry(EntryVisitor.visit_i64(val)?);
+        Ok(set)
+    }
+
+    fn visit_u64<E: de::Error>(self, val: u64) -> Result<Self::Value, E> {
+        let mut set = StatusCodeSet::new();
+        set.insert_entry(EntryVisitor.visit_u64(val)?);
+        Ok(set)
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        let mut set = StatusCodeSet::new();
+        set.insert_entry(EntryVisitor.visit_str(val)?);
+        Ok(set)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut set = StatusCodeSet::new();
+        while let Some(entry) = seq.next_element()? {
+            set.insert_entry(entry);
+        }
+        Ok(set)
+    }
+}
+
+struct RangesVisitor;
+
+impl<'de> de::Visitor<'de> for RangesVisitor {
+    type Value = StatusCodeSet;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of status code ranges")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut set = StatusCodeSet::new();
+        while let Some((start, end)) = seq.next_element::<(u16, u16)>()? {
+            if !(MIN..=MAX).contains(&start) || !(start..=MAX).contains(&end) {
+                return Err(de::Error::custom(format!(
+                    "invalid status code range {start}-{end}"
+                )));
+            }
+            set.insert_entry(Entry::Range(start, end));
+        }
+        Ok(set)
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusCodeSet {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        if de.is_human_readable() {
+            de.deserialize_any(Visitor)
+        } else {
+            de.deserialize_seq(RangesVisitor)
+        }
+    }
+}
@@ -0,0 +1,1032 @@
+// Structured Field Values for HTTP (RFC 8941).
+//
+// Human-readable formats represent a field with native values:
+// - integers and decimals as numbers, strings as strings and booleans as booleans
+// - tokens as `{"token": "..."}` and byte sequences as `{"bytes": "<base64>"}`
+// - items with parameters as `{"value": ..., "params": {...}}`
+// - inner lists as sequences, or `{"items": [...], "params": {...}}` with parameters
+// - lists as sequences and dictionaries as maps
+//
+// Values that aren't valid structured fields are written as `{"$raw": ...}` holding the
+// `header_value` representation of the value.
+
+use std::fmt::{self, Write};
+
+use http::{HeaderName, HeaderValue};
+use serde::{
+    de::{self, DeserializeSeed},
+    ser::{SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{base64, header_map::ValuesSeed, limits};
+
+const TOKEN_KEY: &str = "token";
+const BYTES_KEY: &str = "bytes";
+const VALUE_KEY: &str = "value";
+const PARAMS_KEY: &str = "params";
+const ITEMS_KEY: &str = "items";
+pub(crate) const RAW_KEY: &str = "$raw";
+const KEYS: &[&str] = &[
+    TOKEN_KEY, BYTES_KEY, VALUE_KEY, PARAMS_KEY, ITEMS_KEY, RAW_KEY,
+];
+
+const MAX_INTEGER: i64 = 999_999_999_999_999;
+const MAX_DECIMAL: i64 = 999_999_999_999_999;
+
+/// The type of an RFC 8941 structured field, for headers that
+/// [`header_map::structured`](crate::header_map::structured) doesn't know.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// A list, as in `Accept-CH`.
+    List,
+    /// A dictionary, as in `Priority`.
+    Dictionary,
+    /// A single item, as in `Sec-CH-UA-Mobile`.
+    Item,
+}
+
+impl Kind {
+    // The structured headers known to `header_map::structured`.
+    pub(crate) fn of(name: &HeaderName) -> Option<Kind> {
+        let kind = match name.as_str() {
+            "accept-ch"
+            | "cache-status"
+            | "client-cert-chain"
+            | "proxy-status"
+            | "sec-ch-ua"
+            | "sec-ch-ua-full-version-list" => Kind::List,
+            "cdn-cache-control"
+            | "content-digest"
+            | "priority"
+            | "repr-digest"
+            | "signature"
+            | "signature-input"
+            | "want-content-digest"
+            | "want-repr-digest" => Kind::Dictionary,
+            "client-cert"
+            | "sec-ch-prefers-color-scheme"
+            | "sec-ch-prefers-reduced-motion"
+            | "sec-ch-ua-arch"
+            | "sec-ch-ua-bitness"
+            | "sec-ch-ua-full-version"
+            | "sec-ch-ua-mobile"
+            | "sec-ch-ua-model"
+            | "sec-ch-ua-platform"
+            | "sec-ch-ua-platform-version"
+            | "sec-ch-ua-wow64" => Kind::Item,
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    // The kind of `name` in `known`, or among the structured headers known to
+    // `header_map::structured`.
+    pub(crate) fn of_known(name: &HeaderName, known: &[(HeaderName, Kind)]) -> Option<Kind> {
+        known
+            .iter()
+            .find(|(known, _)| known == name)
+            .map(|(_, kind)| *kind)
+            .or_else(|| Kind::of(name))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum BareItem {
+    Integer(i64),
+    // In thousandths.
+    Decimal(i64),
+    String(String),
+    Token(String),
+    ByteSequence(Vec<u8>),
+    Boolean(bool),
+}
+
+type Params = Vec<(String, BareItem)>;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Item {
+    bare: BareItem,
+    params: Params,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Member {
+    Item(Item),
+    InnerList(Vec<Item>, Params),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Field {
+    List(Vec<Member>),
+    Dictionary(Vec<(String, Member)>),
+    Item(Item),
+}
+
+fn insert<T>(entries: &mut Vec<(String, T)>, key: String, value: T) {
+    match entries.iter_mut().find(|(k, _)| *k == key) {
+        Some(entry) => entry.1 = value,
+        None => entries.push((key, value)),
+    }
+}
+
+// ===== Parsing =====
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+type ParseResult<T> = Result<T, &'static str>;
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let next = self.peek();
+        self.pos += 1;
+        next
+    }
+
+    fn skip_sp(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_ows(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn is_end(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    fn parse(kind: Kind, input: &'a [u8]) -> ParseResult<Field> {
+        let mut parser = Parser { input, pos: 0 };
+        parser.skip_sp();
+        let field = match kind {
+            Kind::List => Field::List(parser.parse_list()?),
+            Kind::Dictionary => Field::Dictionary(parser.parse_dictionary()?),
+            Kind::Item => Field::Item(parser.parse_item()?),
+        };
+        parser.skip_sp();
+        if !parser.is_end() {
+            return Err("unexpected trailing characters");
+        }
+        Ok(field)
+    }
+
+    // Parses the separator between members, returning `true` at the end of the field.
+    fn parse_separator(&mut self) -> ParseResult<bool> {
+        self.skip_ows();
+        if self.is_end() {
+            return Ok(true);
+        }
+        if self.next() != Some(b',') {
+            return Err("expected a comma");
+        }
+        self.skip_ows();
+        if self.is_end() {
+            return Err("trailing comma");
+        }
+        Ok(false)
+    }
+
+    fn parse_list(&mut self) -> ParseResult<Vec<Member>> {
+        let mut members = Vec::new();
+        if self.is_end() {
+            return Ok(members);
+        }
+        loop {
+            members.push(self.parse_member()?);
+            if self.parse_separator()? {
+                return Ok(members);
+            }
+        }
+    }
+
+    fn parse_dictionary(&mut self) -> ParseResult<Vec<(String, Member)>> {
+        let mut members = Vec::new();
+        if self.is_end() {
+            return Ok(members);
+        }
+        loop {
+            let key = self.parse_key()?;
+            let member = if self.peek() == Some(b'=') {
+                self.pos += 1;
+                self.parse_member()?
+            } else {
+                Member::Item(Item {
+                    bare: BareItem::Boolean(true),
+                    params: self.parse_params()?,
+                })
+            };
+            insert(&mut members, key, member);
+            if self.parse_separator()? {
+                return Ok(members);
+            }
+        }
+    }
+
+    fn parse_member(&mut self) -> ParseResult<Member> {
+        if self.peek() == Some(b'(') {
+            self.parse_inner_list()
+        } else {
+            self.parse_item().map(Member::Item)
+        }
+    }
+
+    fn parse_inner_list(&mut self) -> ParseResult<Member> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_sp();
+            match self.peek() {
+                Some(b')') => {
+                    self.pos += 1;
+                    return Ok(Member::InnerList(items, self.parse_params()?));
+                }
+                Some(_) => {
+                    items.push(self.parse_item()?);
+                    if !matches!(self.peek(), Some(b' ' | b')')) {
+                        return Err("expected a space or `)` in inner list");
+                    }
+                }
+                None => return Err("unterminated inner list"),
+            }
+        }
+    }
+
+    fn parse_item(&mut self) -> ParseResult<Item> {
+        Ok(Item {
+            bare: self.parse_bare()?,
+            params: self.parse_params()?,
+        })
+    }
+
+    fn parse_params(&mut self) -> ParseResult<Params> {
+        let mut params = Vec::new();
+        while self.peek() == Some(b';') {
+            self.pos += 1;
+            self.skip_sp();
+            let key = self.parse_key()?;
+            let value = if self.peek() == Some(b'=') {
+                self.pos += 1;
+                self.parse_bare()?
+            } else {
+                BareItem::Boolean(true)
+            };
+            insert(&mut params, key, value);
+        }
+        Ok(params)
+    }
+
+    fn parse_key(&mut self) -> ParseResult<String> {
+        let start = self.pos;
+        match self.peek() {
+            Some(b'a'..=b'z' | b'*') => self.pos += 1,
+            _ => return Err("expected a key"),
+        }
+        while matches!(self.peek(), Some(c) if is_key_char(c)) {
+            self.pos += 1;
+        }
+        Ok(ascii(&self.input[start..self.pos]))
+    }
+
+    fn parse_bare(&mut self) -> ParseResult<BareItem> {
+        match self.peek() {
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(b'"') => self.parse_string(),
+            Some(b':') => self.parse_byte_sequence(),
+            Some(b'?') => self.parse_boolean(),
+            Some(c) if c.is_ascii_alphabetic() || c == b'*' => self.parse_token(),
+            _ => Err("expected an item"),
+        }
+    }
+
+    fn parse_number(&mut self) -> ParseResult<BareItem> {
+        let negative = self.peek() == Some(b'-');
+        if negative {
+            self.pos += 1;
+        }
+        let start = self.pos;
+        let mut dot = None;
+        while let Some(c) = self.peek() {
+            match c {
+                b'0'..=b'9' => {}
+                b'.' if dot.is_none() && self.pos > start => {
+                    if self.pos - start > 12 {
+                        return Err("decimal has too many integer digits");
+                    }
+                    dot = Some(self.pos);
+                }
+                _ => break,
+            }
+            self.pos += 1;
+            if self.pos - start > if dot.is_some() { 16 } else { 15 } {
+                return Err("number is too long");
+            }
+        }
+        if self.pos == start {
+            return Err("expected a digit");
+        }
+
+        let digits = &self.input[start..self.pos];
+        let sign = if negative { -1 } else { 1 };
+        match dot {
+            None => Ok(BareItem::Integer(sign * parse_digits(digits))),
+            Some(dot) => {
+                let (integer, fraction) = (&digits[..dot - start], &digits[dot - start + 1..]);
+                if fraction.is_empty() || fraction.len() > 3 {
+                    return Err("decimal must have one to three fractional digits");
+                }
+                let scale = 10i64.pow(3 - fraction.len() as u32);
+                let value = parse_digits(integer) * 1000 + parse_digits(fraction) * scale;
+                Ok(BareItem::Decimal(sign * value))
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> ParseResult<BareItem> {
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            match self.next() {
+                Some(b'\\') => match self.next() {
+                    Some(c @ (b'"' | b'\\')) => out.push(c.into()),
+                    _ => return Err("invalid escape in string"),
+                },
+                Some(b'"') => return Ok(BareItem::String(out)),
+                Some(c @ 0x20..=0x7e) => out.push(c.into()),
+                Some(_) => return Err("invalid character in string"),
+                None => return Err("unterminated string"),
+            }
+        }
+    }
+
+    fn parse_token(&mut self) -> ParseResult<BareItem> {
+        let start = self.pos;
+        self.pos += 1;
+        while matches!(self.peek(), Some(c) if is_token_char(c)) {
+            self.pos += 1;
+        }
+        Ok(BareItem::Token(ascii(&self.input[start..self.pos])))
+    }
+
+    fn parse_byte_sequence(&mut self) -> ParseResult<BareItem> {
+        self.pos += 1;
+        let start = self.pos;
+        loop {
+            match self.next() {
+                Some(b':') => break,
+                Some(c) if c.is_ascii_alphanumeric() || matches!(c, b'+' | b'/' | b'=') => {}
+                _ => return Err("invalid byte sequence"),
+            }
+        }
+        let encoded = ascii(&self.input[start..self.pos - 1]);
+        base64::decode(&encoded).map(BareItem::ByteSequence)
+    }
+
+    fn parse_boolean(&mut self) -> ParseResult<BareItem> {
+        self.pos += 1;
+        match self.next() {
+            Some(b'1') => Ok(BareItem::Boolean(true)),
+            Some(b'0') => Ok(BareItem::Boolean(false)),
+            _ => Err("invalid boolean"),
+        }
+    }
+}
+
+fn is_key_char(c: u8) -> bool {
+    matches!(c, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.' | b'*')
+}
+
+fn is_token_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~:/".contains(&c)
+}
+
+fn parse_digits(digits: &[u8]) -> i64 {
+    digits
+        .iter()
+        .fold(0, |acc, d| acc * 10 + i64::from(d - b'0'))
+}
+
+fn ascii(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| char::from(*b)).collect()
+}
+
+// ===== Serializing to text =====
+
+fn write_field(field: &Field, out: &mut String) -> Result<(), &'static str> {
+    match field {
+        Field::List(members) => {
+            for (i, member) in members.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_member(member, out)?;
+            }
+        }
+        Field::Dictionary(members) => {
+            for (i, (key, member)) in members.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_key(key, out)?;
+                match member {
+                    Member::Item(Item {
+                        bare: BareItem::Boolean(true),
+                        params,
+                    }) => write_params(params, out)?,
+                    member => {
+                        out.push('=');
+                        write_member(member, out)?;
+                    }
+                }
+            }
+        }
+        Field::Item(item) => write_item(item, out)?,
+    }
+    Ok(())
+}
+
+fn write_member(member: &Member, out: &mut String) -> Result<(), &'static str> {
+    match member {
+        Member::Item(item) => write_item(item, out),
+        Member::InnerList(items, params) => {
+            out.push('(');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(' ');
+                }
+                write_item(item, out)?;
+            }
+            out.push(')');
+            write_params(params, out)
+        }
+    }
+}
+
+fn write_item(item: &Item, out: &mut String) -> Result<(), &'static str> {
+    write_bare(&item.bare, out)?;
+    write_params(&item.params, out)
+}
+
+fn write_params(params: &Params, out: &mut String) -> Result<(), &'static str> {
+    for (key, value) in params {
+        out.push(';');
+        write_key(key, out)?;
+        if *value != BareItem::Boolean(true) {
+            out.push('=');
+            write_bare(value, out)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_key(key: &str, out: &mut String) -> Result<(), &'static str> {
+    let valid =
+        matches!(key.as_bytes().first(), Some(b'a'..=b'z' | b'*')) && key.bytes().all(is_key_char);
+    if !valid {
+        return Err("invalid key");
+    }
+    out.push_str(key);
+    Ok(())
+}
+
+fn write_bare(bare: &BareItem, out: &mut String) -> Result<(), &'static str> {
+    match bare {
+        BareItem::Integer(val) => {
+            if val.unsigned_abs() > MAX_INTEGER as u64 {
+                return Err("integer out of range");
+            }
+            let _ = write!(out, "{val}");
+        }
+        BareItem::Decimal(val) => {
+            if val.unsigned_abs() > MAX_DECIMAL as u64 {
+                return Err("decimal out of range");
+            }
+            if *val < 0 {
+                out.push('-');
+            }
+            let (integer, fraction) = (val.unsigned_abs() / 1000, val.unsigned_abs() % 1000);
+            let fraction = format!("{fraction:03}");
+            let fraction = fraction.trim_end_matches('0');
+            let fraction = if fraction.is_empty() { "0" } else { fraction };
+            let _ = write!(out, "{integer}.{fraction}");
+        }
+        BareItem::String(val) => {
+            out.push('"');
+            for c in val.chars() {
+                match c {
+                    '"' | '\\' => {
+                        out.push('\\');
+                        out.push(c);
+                    }
+                    ' '..='~' => out.push(c),
+                    _ => return Err("invalid character in string"),
+                }
+            }
+            out.push('"');
+        }
+        BareItem::Token(val) => {
+            let valid = matches!(val.as_bytes().first(), Some(c) if c.is_ascii_alphabetic() || *c == b'*')
+                && val.bytes().all(is_token_char);
+            if !valid {
+                return Err("invalid token");
+            }
+            out.push_str(val);
+        }
+        BareItem::ByteSequence(val) => {
+            out.push(':');
+            out.push_str(&base64::encode(val));
+            out.push(':');
+        }
+        BareItem::Boolean(val) => out.push_str(if *val { "?1" } else { "?0" }),
+    }
+    Ok(())
+}
+
+// ===== Serde representation =====
+
+struct SingleEntry<'a, V: ?Sized>(&'a str, &'a V);
+
+impl<'a, V: Serialize + ?Sized> Serialize for SingleEntry<'a, V> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let mut map = ser.serialize_map(Some(1))?;
+        map.serialize_entry(self.0, self.1)?;
+        map.end()
+    }
+}
+
+struct ParamsWrapper<'a>(&'a Params);
+
+impl<'a> Serialize for ParamsWrapper<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+impl Serialize for BareItem {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        match self {
+            BareItem::Integer(val) => ser.serialize_i64(*val),
+            BareItem::Decimal(val) => ser.serialize_f64(*val as f64 / 1000.0),
+            BareItem::String(val) => ser.serialize_str(val),
+            BareItem::Token(val) => SingleEntry(TOKEN_KEY, val.as_str()).serialize(ser),
+            BareItem::ByteSequence(val) => {
+                SingleEntry(BYTES_KEY, base64::encode(val).as_str()).serialize(ser)
+            }
+            BareItem::Boolean(val) => ser.serialize_bool(*val),
+        }
+    }
+}
+
+impl Serialize for Item {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        if self.params.is_empty() {
+            return self.bare.serialize(ser);
+        }
+        let mut map = ser.serialize_map(Some(2))?;
+        map.serialize_entry(VALUE_KEY, &self.bare)?;
+        map.serialize_entry(PARAMS_KEY, &ParamsWrapper(&self.params))?;
+        map.end()
+    }
+}
+
+impl Serialize for Member {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Member::Item(item) => item.serialize(ser),
+            Member::InnerList(items, params) if params.is_empty() => ser.collect_seq(items),
+            Member::InnerList(items, params) => {
+                let mut map = ser.serialize_map(Some(2))?;
+                map.serialize_entry(ITEMS_KEY, items)?;
+                map.serialize_entry(PARAMS_KEY, &ParamsWrapper(params))?;
+                map.end()
+            }
+        }
+    }
+}
+
+impl Serialize for Field {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Field::List(members) => {
+                let mut seq = ser.serialize_seq(Some(members.len()))?;
+                for member in members {
+                    seq.serialize_element(member)?;
+                }
+                seq.end()
+            }
+            Field::Dictionary(members) => ser.collect_map(members.iter().map(|(k, v)| (k, v))),
+            Field::Item(item) => item.serialize(ser),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Shape {
+    Bare,
+    Item,
+    Member,
+}
+
+// Deserializes a bare item, an item or a member, depending on `shape`.
+#[derive(Clone, Copy)]
+struct MemberVisitor {
+    shape: Shape,
+}
+
+impl MemberVisitor {
+    fn bare(bare: BareItem) -> Member {
+        Member::Item(Item {
+            bare,
+            params: Vec::new(),
+        })
+    }
+}
+
+impl<'de> de::Visitor<'de> for MemberVisitor {
+    type Value = Member;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self.shape {
+            Shape::Bare => "a structured field bare item",
+            Shape::Item => "a structured field item",
+            Shape::Member => "a structured field item or inner list",
+        })
+    }
+
+    fn visit_bool<E: de::Error>(self, val: bool) -> Result<Self::Value, E> {
+        Ok(Self::bare(BareItem::Boolean(val)))
+    }
+
+    fn visit_i64<E: de::Error>(self, val: i64) -> Result<Self::Value, E> {
+        if val.unsigned_abs() > MAX_INTEGER as u64 {
+            return Err(E::invalid_value(de::Unexpected::Signed(val), &self));
+        }
+        Ok(Self::bare(BareItem::Integer(val)))
+    }
+
+    fn visit_u64<E: de::Error>(self, val: u64) -> Result<Self::Value, E> {
+        match i64::try_from(val) {
+            Ok(val) if val <= MAX_INTEGER => self.visit_i64(val),
+            _ => Err(E::invalid_value(de::Unexpected::Unsigned(val), &self)),
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, val: f64) -> Result<Self::Value, E> {
+        let thousandths = (val * 1000.0).round();
+        if !thousandths.is_finite() || thousandths.abs() > MAX_DECIMAL as f64 {
+            return Err(E::invalid_value(de::Unexpected::Float(val), &self));
+        }
+        Ok(Self::bare(BareItem::Decimal(thousandths as i64)))
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        Ok(Self::bare(BareItem::String(val.to_owned())))
+    }
+
+    fn visit_string<E: de::Error>(self, val: String) -> Result<Self::Value, E> {
+        Ok(Self::bare(BareItem::String(val)))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        if self.shape != Shape::Member {
+            return Err(de::Error::invalid_type(de::Unexpected::Seq, &self));
+        }
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element::<ItemDe>()? {
+            items.push(item.0);
+        }
+        Ok(Member::InnerList(items, Vec::new()))
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        let first = map.next_key()?;
+        self.visit_entries(first, map)
+    }
+}
+
+impl MemberVisitor {
+    // Visits the entries of a map whose first key has already been read.
+    fn visit_entries<'de, M>(self, first: Option<String>, mut map: M) -> Result<Member, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        let mut bare = None;
+        let mut items = None;
+        let mut params = None;
+        let mut next = first;
+        while let Some(key) = next {
+            match key.as_str() {
+                TOKEN_KEY | BYTES_KEY | VALUE_KEY | ITEMS_KEY
+                    if bare.is_some() || items.is_some() =>
+                {
+                    return Err(de::Error::custom(
+                        "expected one of `token`, `bytes`, `value` or `items`",
+                    ));
+                }
+                TOKEN_KEY => bare = Some(BareItem::Token(map.next_value()?)),
+                BYTES_KEY => {
+                    let bytes = base64::decode(&map.next_value::<String>()?);
+                    bare = Some(BareItem::ByteSequence(bytes.map_err(de::Error::custom)?));
+                }
+                VALUE_KEY if self.shape > Shape::Bare => {
+                    bare = Some(map.next_value::<BareDe>()?.0);
+                }
+                ITEMS_KEY if self.shape == Shape::Member => {
+                    let values = map.next_value::<Vec<ItemDe>>()?;
+                    items = Some(values.into_iter().map(|item| item.0).collect());
+                }
+                PARAMS_KEY if self.shape > Shape::Bare && params.is_none() => {
+                    params = Some(map.next_value::<ParamsDe>()?.0);
+                }
+                PARAMS_KEY if params.is_some() => {
+                    return Err(de::Error::duplicate_field(PARAMS_KEY));
+                }
+                _ => return Err(de::Error::unknown_field(&key, KEYS)),
+            }
+            next = map.next_key()?;
+        }
+        let params = params.unwrap_or_default();
+        match (bare, items) {
+            (Some(bare), None) => Ok(Member::Item(Item { bare, params })),
+            (None, Some(items)) => Ok(Member::InnerList(items, params)),
+            _ => Err(de::Error::custom(
+                "expected one of `token`, `bytes`, `value` or `items`",
+            )),
+        }
+    }
+}
+
+struct BareDe(BareItem);
+
+impl<'de> Deserialize<'de> for BareDe {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let visitor = MemberVisitor { shape: Shape::Bare };
+        match de.deserialize_any(visitor)? {
+            Member::Item(item) => Ok(BareDe(item.bare)),
+            Member::InnerList(..) => unreachable!("bare items have no inner lists"),
+        }
+    }
+}
+
+struct ItemDe(Item);
+
+impl<'de> Deserialize<'de> for ItemDe {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let visitor = MemberVisitor { shape: Shape::Item };
+        match de.deserialize_any(visitor)? {
+            Member::Item(item) => Ok(ItemDe(item)),
+            Member::InnerList(..) => unreachable!("items have no inner lists"),
+        }
+    }
+}
+
+struct MemberDe(Member);
+
+impl<'de> Deserialize<'de> for MemberDe {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let visitor = MemberVisitor {
+            shape: Shape::Member,
+        };
+        de.deserialize_any(visitor).map(MemberDe)
+    }
+}
+
+struct ParamsDe(Params);
+
+impl<'de> Deserialize<'de> for ParamsDe {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Params;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("structured field parameters")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: de::MapAccess<'de>,
+            {
+                let mut params = Vec::new();
+                while let Some((key, value)) = map.next_entry::<String, BareDe>()? {
+                    insert(&mut params, key, value.0);
+                }
+                Ok(params)
+            }
+        }
+
+        de.deserialize_map(Visitor).map(ParamsDe)
+    }
+}
+
+// Deserializes the structured representation of a field into its header values, naming the
+// header in errors when known.
+#[derive(Clone, Copy)]
+pub(crate) struct FieldSeed<'a> {
+    pub(crate) kind: Kind,
+    pub(crate) name: Option<&'a HeaderName>,
+}
+
+#[derive(Clone, Copy)]
+struct FieldVisitor<'a> {
+    kind: Kind,
+    name: Option<&'a HeaderName>,
+}
+
+impl<'a> FieldVisitor<'a> {
+    fn into_values<E: de::Error>(field: Field) -> Result<Vec<HeaderValue>, E> {
+        let mut text = String::new();
+        write_field(&field, &mut text).map_err(de::Error::custom)?;
+        limits::check_header_value_len(text.len())?;
+        let value = HeaderValue::try_from(text).map_err(de::Error::custom)?;
+        Ok(vec![value])
+    }
+
+    fn raw<'de, M: de::MapAccess<'de>>(self, mut map: M) -> Result<Vec<HeaderValue>, M::Error> {
+        let values = map.next_value_seed(ValuesSeed { name: self.name })?;
+        let values = values.into_iter().collect();
+        if let Some(key) = map.next_key::<String>()? {
+            return Err(de::Error::unknown_field(&key, &[RAW_KEY]));
+        }
+        Ok(values)
+    }
+}
+
+impl<'de, 'a> de::Visitor<'de> for FieldVisitor<'a> {
+    type Value = Vec<HeaderValue>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self.kind {
+            Kind::List => "a structured field list",
+            Kind::Dictionary => "a structured field dictionary",
+            Kind::Item => "a structured field item",
+        })
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        if self.kind != Kind::List {
+            return Err(de::Error::invalid_type(de::Unexpected::Seq, &self));
+        }
+        let mut members = Vec::new();
+        while let Some(member) = seq.next_element::<MemberDe>()? {
+            members.push(member.0);
+        }
+        Self::into_values(Field::List(members))
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        match self.kind {
+            Kind::List => match map.next_key::<String>()? {
+                Some(key) if key == RAW_KEY => self.raw(map),
+                Some(key) => Err(de::Error::unknown_field(&key, &[RAW_KEY])),
+                None => Err(de::Error::invalid_length(0, &self)),
+            },
+            Kind::Dictionary => {
+                let mut members = Vec::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == RAW_KEY && members.is_empty() {
+                        return self.raw(map);
+                    }
+                    insert(&mut members, key, map.next_value::<MemberDe>()?.0);
+                }
+                Self::into_values(Field::Dictionary(members))
+            }
+            Kind::Item => match map.next_key::<String>()? {
+                Some(key) if key == RAW_KEY => self.raw(map),
+                first => {
+                    Self::item(MemberVisitor { shape: Shape::Item }.visit_entries(first, map)?)
+                }
+            },
+        }
+    }
+
+    fn visit_bool<E: de::Error>(self, val: bool) -> Result<Self::Value, E> {
+        self.scalar(MemberVisitor { shape: Shape::Item }.visit_bool(val)?)
+    }
+
+    fn visit_i64<E: de::Error>(self, val: i64) -> Result<Self::Value, E> {
+        self.scalar(MemberVisitor { shape: Shape::Item }.visit_i64(val)?)
+    }
+
+    fn visit_u64<E: de::Error>(self, val: u64) -> Result<Self::Value, E> {
+        self.scalar(MemberVisitor { shape: Shape::Item }.visit_u64(val)?)
+    }
+
+    fn visit_f64<E: de::Error>(self, val: f64) -> Result<Self::Value, E> {
+        self.scalar(MemberVisitor { shape: Shape::Item }.visit_f64(val)?)
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        self.scalar(MemberVisitor { shape: Shape::Item }.visit_str(val)?)
+    }
+}
+
+impl<'a> FieldVisitor<'a> {
+    fn item<E: de::Error>(member: Member) -> Result<Vec<HeaderValue>, E> {
+        match member {
+            Member::Item(item) => Self::into_values(Field::Item(item)),
+            Member::InnerList(..) => unreachable!("items have no inner lists"),
+        }
+    }
+
+    fn scalar<E: de::Error>(self, member: Member) -> Result<Vec<HeaderValue>, E> {
+        if self.kind != Kind::Item {
+            return Err(de::Error::invalid_type(
+                de::Unexpected::Other("a bare item"),
+                &self,
+            ));
+        }
+        Self::item(member)
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for FieldSeed<'a> {
+    type Value = Vec<HeaderValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(FieldVisitor {
+            kind: self.kind,
+            name: self.name,
+        })
+    }
+}
+
+struct RawValues<'a>(&'a [&'a HeaderValue]);
+
+impl<'a> Serialize for RawValues<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            [value] => crate::header_value::serialize(value, ser),
+            values => {
+                let mut seq = ser.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(&RawValue(value))?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+struct RawValue<'a>(&'a HeaderValue);
+
+impl<'a> Serialize for RawValue<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        crate::header_value::serialize(self.0, ser)
+    }
+}
+
+// Serializes the values of a field in the human-readable structured representation. A single
+// value that parses as `kind` is written as a structured field, anything else as `$raw`.
+pub(crate) fn serialize_values<S>(
+    kind: Kind,
+    values: &[&HeaderValue],
+    ser: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if let [value] = values {
+        if let Ok(field) = Parser::parse(kind, value.as_bytes()) {
+            return field.serialize(ser);
+        }
+    }
+    SingleEntry(RAW_KEY, &RawValues(values)).serialize(ser)
+}
+
+pub(crate) fn deserialize_value<'de, D>(kind: Kind, de: D) -> Result<HeaderValue, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut values = FieldSeed { kind, name: None }.deserialize(de)?;
+    if values.len() != 1 {
+        return Err(de::Error::invalid_length(
+            values.len(),
+            &"a single header value",
+        ));
+    }
+    Ok(values.remove(0))
+}
@@ -0,0 +1,78 @@
+use std::{fmt, marker::PhantomData};
+
+use headers::Header;
+use http::HeaderValue;
+use serde::{de, de::DeserializeSeed, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::header_map::ValuesSeed;
+
+struct BorrowedValueWrapper<'a>(&'a HeaderValue);
+
+impl<'a> Serialize for BorrowedValueWrapper<'a> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        crate::header_value::serialize(self.0, ser)
+    }
+}
+
+#[derive(Deserialize)]
+struct ValueWrapper(#[serde(with = "crate::header_value")] HeaderValue);
+
+// Human-readable formats write a single value as a `header_value`, and several as a sequence.
+// Binary formats always write a sequence.
+pub fn serialize<H, S>(val: &H, ser: S) -> Result<S::Ok, S::Error>
+where
+    H: Header,
+    S: Serializer,
+{
+    let mut values = Vec::new();
+    val.encode(&mut values);
+    match values.as_slice() {
+        [value] if ser.is_human_readable() => crate::header_value::serialize(value, ser),
+        values => ser.collect_seq(values.iter().map(BorrowedValueWrapper)),
+    }
+}
+
+fn decode<H: Header, E: de::Error>(values: &[HeaderValue]) -> Result<H, E> {
+    H::decode(&mut values.iter())
+        .map_err(|_| de::Error::custom(format!("invalid {} header", H::name())))
+}
+
+struct Visitor<H> {
+    ph: PhantomData<H>,
+}
+
+impl<'de, H: Header> de::Visitor<'de> for Visitor<H> {
+    type Value = H;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a {} header", H::name())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(crate::limits::capacity(seq.size_hint()));
+        while let Some(value) = seq.next_element::<ValueWrapper>()? {
+            values.push(value.0);
+        }
+        decode(&values)
+    }
+}
+
+pub fn deserialize<'de, D, H>(de: D) -> Result<H, D::Error>
+where
+    D: Deserializer<'de>,
+    H: Header,
+{
+    if !de.is_human_readable() {
+        return de.deserialize_seq(Visitor { ph: PhantomData });
+    }
+    let values = ValuesSeed {
+        name: Some(H::name()),
+    }
+    .deserialize(de)?;
+    decode(&values.into_iter().collect::<Vec<_>>())
+}
+
+derive_extension_types!(H, H: headers::Header);
@@ -1,4 +1,8 @@
-use serde::Serializer;
+use std::fmt;
+
+use serde::{de, Serializer};
+
+use crate::limits;
 
 type Type = http::Uri;
 const EXPECT_MESSAGE: &str = "a uri string";
@@ -7,13 +11,25 @@ pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
     ser.collect_str(val)
 }
 
-create_visitor!(
-    Visitor,
-    Type,
-    EXPECT_MESSAGE,
-    (visit_str, &str),
-    (visit_string, String)
-);
+struct Visitor;
+
+impl<'de> de::Visitor<'de> for Visitor {
+    type Value = Type;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(EXPECT_MESSAGE)
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        limits::check_uri_len(val.len())?;
+        val.try_into().map_err(de::Error::custom)
+    }
+
+    fn visit_string<E: de::Error>(self, val: String) -> Result<Self::Value, E> {
+        limits::check_uri_len(val.len())?;
+        val.try_into().map_err(de::Error::custom)
+    }
+}
 deserialize_string!(Visitor, Type);
 
 derive_extension_types!(super::Type);
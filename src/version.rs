@@ -1,11 +1,36 @@
 use std::fmt;
 
-use serde::{de, Serializer};
+use serde::{de, Deserializer, Serializer};
 
 type Type = http::Version;
 const EXPECT_MESSAGE: &str = "a version string";
 
+// Binary formats write known versions as a single tag byte.
+const COMPACT: [Type; 5] = [
+    Type::HTTP_09,
+    Type::HTTP_10,
+    Type::HTTP_11,
+    Type::HTTP_2,
+    Type::HTTP_3,
+];
+const UNKNOWN_TAG: u8 = u8::MAX;
+const UNKNOWN_MESSAGE: &str = "version is unknown to http-serde-ext and cannot be deserialized";
+
+/// Serializes a known version as `"HTTP/1.1"` style strings, or as a single tag byte in
+/// binary formats.
+///
+/// A version this crate doesn't know, e.g. one added to [`http`] later, is written as its
+/// [`Debug`](std::fmt::Debug) text in human-readable formats and as the reserved tag `255` in
+/// binary formats. Deserializing either fails with an error, as the version cannot be
+/// constructed again. Use [`strict`] to reject such versions when serializing instead.
 pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
+    if !ser.is_human_readable() {
+        return match COMPACT.iter().position(|version| version == val) {
+            Some(tag) => ser.serialize_u8(tag as u8),
+            None => ser.serialize_u8(UNKNOWN_TAG),
+        };
+    }
+
     let val = match *val {
         Type::HTTP_09 => "HTTP/0.9",
         Type::HTTP_10 => "HTTP/1.0",
@@ -13,7 +38,7 @@ pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
         Type::HTTP_2 => "HTTP/2.0",
         Type::HTTP_3 => "HTTP/3.0",
         _ => {
-            return ser.serialize_str(format!("{val:?}").as_str());
+            return ser.collect_str(&format_args!("{val:?}"));
         }
     };
     ser.serialize_str(val)
@@ -28,24 +53,171 @@ impl<'de> de::Visitor<'de> for Visitor {
         formatter.write_str(EXPECT_MESSAGE)
     }
 
+    // Accepts `HTTP/1.1` style names in any case, the short `HTTP/2` and `HTTP/3` spellings,
+    // ALPN identifiers such as `http/1.1`, `h2` and `h3`, and bare numbers such as `1.1`.
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        let version = match v {
-            "HTTP/0.9" => Type::HTTP_09,
-            "HTTP/1.0" => Type::HTTP_10,
-            "HTTP/1.1" => Type::HTTP_11,
-            "HTTP/2.0" => Type::HTTP_2,
-            "HTTP/3.0" => Type::HTTP_3,
+        let number = match v.get(..5) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("HTTP/") => &v[5..],
+            _ => match v {
+                "h2" | "h2c" => return Ok(Type::HTTP_2),
+                "h3" => return Ok(Type::HTTP_3),
+                _ => v,
+            },
+        };
+        let version = match number {
+            "0.9" => Type::HTTP_09,
+            "1.0" => Type::HTTP_10,
+            "1.1" => Type::HTTP_11,
+            "2" | "2.0" => Type::HTTP_2,
+            "3" | "3.0" => Type::HTTP_3,
             _ => return Err(E::invalid_value(de::Unexpected::Str(v), &self)),
         };
         Ok(version)
     }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match v {
+            2 => Ok(Type::HTTP_2),
+            3 => Ok(Type::HTTP_3),
+            _ => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match u64::try_from(v) {
+            Ok(v) => self.visit_u64(v),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Signed(v), &self)),
+        }
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let version = match v {
+            0.9 => Type::HTTP_09,
+            1.0 => Type::HTTP_10,
+            1.1 => Type::HTTP_11,
+            2.0 => Type::HTTP_2,
+            3.0 => Type::HTTP_3,
+            _ => return Err(E::invalid_value(de::Unexpected::Float(v), &self)),
+        };
+        Ok(version)
+    }
+}
+
+struct CompactVisitor;
+
+impl<'de> de::Visitor<'de> for CompactVisitor {
+    type Value = Type;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(EXPECT_MESSAGE)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v == u64::from(UNKNOWN_TAG) {
+            return Err(E::custom(UNKNOWN_MESSAGE));
+        }
+        usize::try_from(v)
+            .ok()
+            .and_then(|tag| COMPACT.get(tag))
+            .copied()
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+    }
 }
 
-deserialize_str!(Visitor, Type);
+pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if de.is_human_readable() {
+        de.deserialize_any(Visitor)
+    } else {
+        de.deserialize_u8(CompactVisitor)
+    }
+}
 
 derive_extension_types!(super::Type);
 derive_hash_types!(super::Type);
 derive_ord_types!(super::Type);
+
+/// Serializes a [`Version`](http::Version) as its ALPN protocol identifier, one of
+/// `http/0.9`, `http/1.0`, `http/1.1`, `h2` or `h3`.
+///
+/// Deserializing accepts the same spellings as [`version`](super). Binary formats use the
+/// same compact encoding as [`version`](super).
+pub mod alpn {
+    use serde::{ser, Deserializer, Serializer};
+
+    use super::Type;
+
+    pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        if !ser.is_human_readable() {
+            return super::serialize(val, ser);
+        }
+
+        let val = match *val {
+            Type::HTTP_09 => "http/0.9",
+            Type::HTTP_10 => "http/1.0",
+            Type::HTTP_11 => "http/1.1",
+            Type::HTTP_2 => "h2",
+            Type::HTTP_3 => "h3",
+            _ => {
+                use ser::Error;
+                return Err(Error::custom(format!("no ALPN identifier for {val:?}")));
+            }
+        };
+        ser.serialize_str(val)
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize(de)
+    }
+
+    derive_extension_types!(super::Type);
+    derive_hash_types!(super::Type);
+    derive_ord_types!(super::Type);
+}
+
+/// Same representation as [`version`](super), but serializing a version this crate doesn't
+/// know fails instead of writing data that cannot be deserialized.
+pub mod strict {
+    use serde::{ser, Deserializer, Serializer};
+
+    use super::{Type, COMPACT};
+
+    pub fn serialize<S: Serializer>(val: &Type, ser: S) -> Result<S::Ok, S::Error> {
+        if !COMPACT.contains(val) {
+            use ser::Error;
+            return Err(Error::custom(format!("unknown version {val:?}")));
+        }
+        super::serialize(val, ser)
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Type, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize(de)
+    }
+
+    derive_extension_types!(super::Type);
+    derive_hash_types!(super::Type);
+    derive_ord_types!(super::Type);
+}
@@ -567,6 +567,127 @@ fn test_header_map_roundtrip() {
     );
 }
 
+#[test]
+fn test_header_map_original_case() {
+    use http_serde_ext::HeaderCaseMap;
+
+    let mut map = HeaderMap::new();
+    map.append("content-type", HeaderValue::from_static("text/html"));
+    map.append("x-bread", HeaderValue::from_static("Baguette"));
+    map.append("x-bread", HeaderValue::from_static("Ficelle"));
+    map.append("x-bread", HeaderValue::from_static("Pain"));
+    map.append("accept", HeaderValue::from_static("*/*"));
+
+    let mut case = HeaderCaseMap::new();
+    case.append("Content-Type").unwrap();
+    case.append("X-Bread").unwrap();
+    case.append("X-Bread").unwrap();
+    case.append("x-BREAD").unwrap();
+
+    fn equate(a: &(HeaderMap, HeaderCaseMap), b: &(HeaderMap, HeaderCaseMap)) {
+        assert_eq!(a.0, b.0);
+        for name in a.0.keys() {
+            let a = a.1.get_all(name).collect::<Vec<_>>();
+            let b = b.1.get_all(name).collect::<Vec<_>>();
+            assert!(a.starts_with(&b), "{a:?} {b:?}");
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(
+        #[serde(with = "http_serde_ext::header_map::original_case")] (HeaderMap, HeaderCaseMap),
+    );
+
+    let ser = serde_json::to_value(Wrapper((map.clone(), case.clone()))).unwrap();
+    assert_eq!(
+        ser,
+        json!({
+            "Content-Type": "text/html",
+            "X-Bread": ["Baguette", "Ficelle"],
+            "x-BREAD": "Pain",
+            "accept": "*/*"
+        })
+    );
+    let de: Wrapper = serde_json::from_value(ser).unwrap();
+    equate(&de.0, &(map.clone(), case.clone()));
+
+    serde_cbor_roundtrip!(
+        (HeaderMap, HeaderCaseMap),
+        (map.clone(), case.clone()),
+        equate,
+        "http_serde_ext::header_map::original_case"
+    );
+    bincode_roundtrip!(
+        (HeaderMap, HeaderCaseMap),
+        (map.clone(), case.clone()),
+        equate,
+        "http_serde_ext::header_map::original_case"
+    );
+    postcard_roundtrip!(
+        (HeaderMap, HeaderCaseMap),
+        (map.clone(), case.clone()),
+        equate,
+        "http_serde_ext::header_map::original_case"
+    );
+
+    let mut request = Request::new(());
+    *request.headers_mut() = map.clone();
+    request.extensions_mut().insert(case.clone());
+
+    #[derive(Serialize, Deserialize)]
+    struct RequestWrapper(#[serde(with = "http_serde_ext::request::original_case")] Request<()>);
+
+    let ser = serde_json::to_value(RequestWrapper(request.clone())).unwrap();
+    assert_eq!(
+        ser["head"]["headers"],
+        json!({
+            "Content-Type": "text/html",
+            "X-Bread": ["Baguette", "Ficelle"],
+            "x-BREAD": "Pain",
+            "accept": "*/*"
+        })
+    );
+    let de: RequestWrapper = serde_json::from_value(ser).unwrap();
+    assert_eq!(de.0.headers(), &map);
+    let de_case = de.0.extensions().get::<HeaderCaseMap>().unwrap();
+    assert_eq!(
+        de_case
+            .get_all(&HeaderName::from_static("x-bread"))
+            .collect::<Vec<_>>(),
+        ["X-Bread", "X-Bread", "x-BREAD"]
+    );
+
+    let ser = bincode::serialize(&RequestWrapper(request.clone())).unwrap();
+    let de: RequestWrapper = bincode::deserialize(&ser).unwrap();
+    assert_eq!(de.0.headers(), &map);
+    assert!(de.0.extensions().get::<HeaderCaseMap>().is_some());
+
+    #[derive(Serialize, Deserialize)]
+    struct ResponseWrapper(#[serde(with = "http_serde_ext::response::original_case")] Response<()>);
+
+    let mut response = Response::new(());
+    *response.headers_mut() = map.clone();
+    response.extensions_mut().insert(case.clone());
+    let ser = serde_yaml::to_string(&ResponseWrapper(response)).unwrap();
+    assert!(ser.contains("Content-Type: text/html"));
+    let de: ResponseWrapper = serde_yaml::from_str(&ser).unwrap();
+    assert_eq!(de.0.headers(), &map);
+    assert!(de.0.extensions().get::<HeaderCaseMap>().is_some());
+
+    // Lowercase names don't add an extension.
+    let mut response = Response::new(());
+    *response.headers_mut() = map.clone();
+    let ser = serde_json::to_value(ResponseWrapper(response)).unwrap();
+    let de: ResponseWrapper = serde_json::from_value(ser).unwrap();
+    assert!(de.0.extensions().is_empty());
+
+    #[derive(Serialize)]
+    struct DefaultWrapper(#[serde(with = "http_serde_ext::request")] Request<()>);
+
+    let res = serde_json::to_value(DefaultWrapper(request));
+    assert_eq!(res.unwrap_err().to_string(), "extensions is not empty");
+}
+
 #[test]
 fn test_header_map_generic_roundtrip() {
     test_all!(
@@ -584,6 +705,23 @@ fn test_header_map_generic_roundtrip() {
         "http_serde_ext::header_map_generic::btree_map"
     );
 
+    #[derive(Debug, Deserialize)]
+    struct Generic(#[serde(with = "http_serde_ext::header_map_generic")] HeaderMap<u8>);
+
+    let res = serde_json::from_value::<Generic>(json!({"x-a": "b"}));
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "invalid value for header x-a: invalid type: string \"b\", expected u8"
+    );
+    let res = serde_json::from_value::<Generic>(json!({"x-a": [1, 256]}));
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "invalid value for header x-a: invalid value: integer `256`, expected u8"
+    );
+    let de: Generic = serde_json::from_value(json!({"x-a": [1, 2], "x-b": 3})).unwrap();
+    assert_eq!(de.0.get_all("x-a").iter().collect::<Vec<_>>(), [&1, &2]);
+    assert_eq!(de.0["x-b"], 3);
+
     let fake: HeaderMap<String> = Faker.fake();
     test_all_no_intermediate_compare!(
         HeaderMap<String>,
@@ -599,6 +737,256 @@ fn test_header_map_generic_roundtrip() {
     );
 }
 
+#[test]
+fn test_header_map_always_seq() {
+    let mut map = HeaderMap::new();
+    map.insert("accept", HeaderValue::from_static("text/html"));
+    test_all!(
+        HeaderMap,
+        map.clone(),
+        json!({"accept": ["text/html"]}),
+        "accept:\n- text/html",
+        "http_serde_ext::header_map::always_seq",
+        "http_serde_ext::header_map::always_seq::option",
+        "http_serde_ext::header_map::always_seq::result",
+        "http_serde_ext::header_map::always_seq::vec",
+        "http_serde_ext::header_map::always_seq::vec_deque",
+        "http_serde_ext::header_map::always_seq::linked_list",
+        "http_serde_ext::header_map::always_seq::hash_map",
+        "http_serde_ext::header_map::always_seq::btree_map"
+    );
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "http_serde_ext::header_map::always_seq")] HeaderMap);
+
+    map.append("accept", HeaderValue::from_static("*/*"));
+    map.insert("x-a", HeaderValue::from_static("b"));
+    let de: Wrapper =
+        serde_json::from_value(json!({"accept": ["text/html", "*/*"], "x-a": "b"})).unwrap();
+    assert_eq!(de.0, map);
+    assert_eq!(
+        serde_json::to_value(de).unwrap(),
+        json!({"accept": ["text/html", "*/*"], "x-a": ["b"]})
+    );
+
+    let mut generic = HeaderMap::<u16>::default();
+    generic.insert("x-a", 1);
+    serde_json_roundtrip!(
+        HeaderMap<u16>,
+        generic.clone(),
+        "http_serde_ext::header_map_generic::always_seq",
+        json!({"x-a": [1]})
+    );
+
+    let fake: HeaderMap<String> = Faker.fake();
+    test_all_no_intermediate_compare!(
+        HeaderMap<String>,
+        fake.clone(),
+        "http_serde_ext::header_map_generic::always_seq",
+        "http_serde_ext::header_map_generic::always_seq::option",
+        "http_serde_ext::header_map_generic::always_seq::result",
+        "http_serde_ext::header_map_generic::always_seq::vec",
+        "http_serde_ext::header_map_generic::always_seq::vec_deque",
+        "http_serde_ext::header_map_generic::always_seq::linked_list",
+        "http_serde_ext::header_map_generic::always_seq::hash_map",
+        "http_serde_ext::header_map_generic::always_seq::btree_map"
+    );
+}
+
+#[test]
+fn test_header_map_pairs() {
+    use http_serde_ext::HeaderOrder;
+
+    let mut map = HeaderMap::new();
+    map.append("accept", HeaderValue::from_static("text/html"));
+    test_all!(
+        HeaderMap,
+        map.clone(),
+        json!([{"name": "accept", "value": "text/html"}]),
+        "- name: accept\n  value: text/html",
+        "http_serde_ext::header_map::pairs",
+        "http_serde_ext::header_map::pairs::option",
+        "http_serde_ext::header_map::pairs::result",
+        "http_serde_ext::header_map::pairs::vec",
+        "http_serde_ext::header_map::pairs::vec_deque",
+        "http_serde_ext::header_map::pairs::linked_list",
+        "http_serde_ext::header_map::pairs::hash_map",
+        "http_serde_ext::header_map::pairs::btree_map"
+    );
+
+    map.append("x-a", HeaderValue::from_static("1"));
+    map.append("accept", HeaderValue::from_bytes(b"\xff").unwrap());
+    serde_json_roundtrip!(
+        HeaderMap,
+        map.clone(),
+        "http_serde_ext::header_map::pairs",
+        json!([
+            {"name": "accept", "value": "text/html"},
+            {"name": "accept", "value": {"base64": "/w=="}},
+            {"name": "x-a", "value": "1"}
+        ])
+    );
+
+    let fake: HeaderMap = Faker.fake();
+    test_all_no_intermediate_compare!(
+        HeaderMap,
+        fake.clone(),
+        "http_serde_ext::header_map::pairs",
+        "http_serde_ext::header_map::pairs::option",
+        "http_serde_ext::header_map::pairs::result",
+        "http_serde_ext::header_map::pairs::vec",
+        "http_serde_ext::header_map::pairs::vec_deque",
+        "http_serde_ext::header_map::pairs::linked_list",
+        "http_serde_ext::header_map::pairs::hash_map",
+        "http_serde_ext::header_map::pairs::btree_map"
+    );
+
+    let mut expected = HeaderOrder::new();
+    expected.append("X-A").unwrap();
+    expected.append("accept").unwrap();
+    expected.append("accept").unwrap();
+    serde_json_roundtrip!(
+        (HeaderMap, HeaderOrder),
+        (map.clone(), expected.clone()),
+        "http_serde_ext::header_map::pairs::with_order",
+        json!([
+            {"name": "x-a", "value": "1"},
+            {"name": "accept", "value": "text/html"},
+            {"name": "accept", "value": {"base64": "/w=="}}
+        ])
+    );
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Wrapper(
+        #[serde(with = "http_serde_ext::header_map::pairs::with_order")] (HeaderMap, HeaderOrder),
+    );
+
+    // Stale names are skipped and unrecorded values go last.
+    let mut partial = HeaderOrder::new();
+    partial.append("host").unwrap();
+    partial.append("accept").unwrap();
+    let ser = serde_json::to_value(Wrapper((map.clone(), partial))).unwrap();
+    assert_eq!(
+        ser,
+        json!([
+            {"name": "accept", "value": "text/html"},
+            {"name": "accept", "value": {"base64": "/w=="}},
+            {"name": "x-a", "value": "1"}
+        ])
+    );
+
+    let mut interleaved = HeaderOrder::new();
+    interleaved.append("accept").unwrap();
+    interleaved.append("x-a").unwrap();
+    interleaved.append("accept").unwrap();
+    test_all!(
+        (HeaderMap, HeaderOrder),
+        (map.clone(), interleaved.clone()),
+        json!([
+            {"name": "accept", "value": "text/html"},
+            {"name": "x-a", "value": "1"},
+            {"name": "accept", "value": {"base64": "/w=="}}
+        ]),
+        "- name: accept\n  value: text/html\n- name: x-a\n  value: '1'\n- name: accept\n  value:\n    base64: /w==",
+        "http_serde_ext::header_map::pairs::with_order",
+        "http_serde_ext::header_map::pairs::with_order::option",
+        "http_serde_ext::header_map::pairs::with_order::result",
+        "http_serde_ext::header_map::pairs::with_order::vec",
+        "http_serde_ext::header_map::pairs::with_order::vec_deque",
+        "http_serde_ext::header_map::pairs::with_order::linked_list",
+        "http_serde_ext::header_map::pairs::with_order::hash_map",
+        "http_serde_ext::header_map::pairs::with_order::btree_map"
+    );
+
+    assert!(serde_json::from_value::<Wrapper>(json!([{"name": "x a", "value": "1"}])).is_err());
+}
+
+#[test]
+fn test_header_map_sorted() {
+    let mut map = HeaderMap::new();
+    map.append("x-b", HeaderValue::from_static("1"));
+    map.append("accept", HeaderValue::from_static("text/html"));
+    map.append("x-b", HeaderValue::from_static("2"));
+    test_all!(
+        HeaderMap,
+        map.clone(),
+        json!({"accept": "text/html", "x-b": ["1", "2"]}),
+        "accept: text/html\nx-b:\n- '1'\n- '2'",
+        "http_serde_ext::header_map::sorted",
+        "http_serde_ext::header_map::sorted::option",
+        "http_serde_ext::header_map::sorted::result",
+        "http_serde_ext::header_map::sorted::vec",
+        "http_serde_ext::header_map::sorted::vec_deque",
+        "http_serde_ext::header_map::sorted::linked_list",
+        "http_serde_ext::header_map::sorted::hash_map",
+        "http_serde_ext::header_map::sorted::btree_map"
+    );
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "http_serde_ext::header_map::sorted")] HeaderMap);
+
+    let mut other = HeaderMap::new();
+    other.append("accept", HeaderValue::from_static("text/html"));
+    other.append("x-b", HeaderValue::from_static("1"));
+    other.append("x-b", HeaderValue::from_static("2"));
+    assert_eq!(
+        serde_json::to_string(&Wrapper(map.clone())).unwrap(),
+        r#"{"accept":"text/html","x-b":["1","2"]}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&Wrapper(map.clone())).unwrap(),
+        serde_json::to_string(&Wrapper(other.clone())).unwrap()
+    );
+    assert_eq!(
+        serde_cbor::to_vec(&Wrapper(map.clone())).unwrap(),
+        serde_cbor::to_vec(&Wrapper(other.clone())).unwrap()
+    );
+
+    let fake: HeaderMap = Faker.fake();
+    test_all_no_intermediate_compare!(
+        HeaderMap,
+        fake.clone(),
+        "http_serde_ext::header_map::sorted",
+        "http_serde_ext::header_map::sorted::option",
+        "http_serde_ext::header_map::sorted::result",
+        "http_serde_ext::header_map::sorted::vec",
+        "http_serde_ext::header_map::sorted::vec_deque",
+        "http_serde_ext::header_map::sorted::linked_list",
+        "http_serde_ext::header_map::sorted::hash_map",
+        "http_serde_ext::header_map::sorted::btree_map"
+    );
+
+    #[derive(Serialize, Deserialize)]
+    struct RequestWrapper(#[serde(with = "http_serde_ext::request::sorted")] Request<()>);
+
+    let mut request = Request::new(());
+    *request.headers_mut() = map.clone();
+    let mut other_request = Request::new(());
+    *other_request.headers_mut() = other.clone();
+    let ser = serde_cbor::to_vec(&RequestWrapper(request)).unwrap();
+    assert_eq!(
+        ser,
+        serde_cbor::to_vec(&RequestWrapper(other_request)).unwrap()
+    );
+    let de: RequestWrapper = serde_cbor::from_slice(&ser).unwrap();
+    assert_eq!(de.0.headers(), &map);
+
+    #[derive(Serialize, Deserialize)]
+    struct ResponseWrapper(#[serde(with = "http_serde_ext::response::sorted")] Response<()>);
+
+    let mut response = Response::new(());
+    *response.headers_mut() = map.clone();
+    let mut other_response = Response::new(());
+    *other_response.headers_mut() = other;
+    let ser = serde_json::to_string(&ResponseWrapper(response)).unwrap();
+    assert_eq!(
+        ser,
+        serde_json::to_string(&ResponseWrapper(other_response)).unwrap()
+    );
+    let de: ResponseWrapper = serde_json::from_str(&ser).unwrap();
+    assert_eq!(de.0.headers(), &map);
+}
+
 #[test]
 fn test_header_name_roundtrip() {
     test_all!(
@@ -711,52 +1099,1590 @@ fn test_header_value_roundtrip() {
     );
 }
 
+#[test]
+fn test_header_value_non_utf8() {
+    let latin1 = HeaderValue::from_bytes(b"attachment; filename=caf\xe9.txt").unwrap();
+    test_all!(
+        HeaderValue,
+        latin1.clone(),
+        json!({"base64": "YXR0YWNobWVudDsgZmlsZW5hbWU9Y2Fm6S50eHQ="}),
+        "base64: YXR0YWNobWVudDsgZmlsZW5hbWU9Y2Fm6S50eHQ=",
+        "http_serde_ext::header_value",
+        "http_serde_ext::header_value::option",
+        "http_serde_ext::header_value::result",
+        "http_serde_ext::header_value::vec",
+        "http_serde_ext::header_value::vec_deque",
+        "http_serde_ext::header_value::linked_list",
+        "http_serde_ext::header_value::hash_map",
+        "http_serde_ext::header_value::btree_map"
+    );
+
+    let utf8 = HeaderValue::from_str("café").unwrap();
+    test_all!(
+        HeaderValue,
+        utf8.clone(),
+        json!("café"),
+        "café",
+        "http_serde_ext::header_value",
+        "http_serde_ext::header_value::option",
+        "http_serde_ext::header_value::result",
+        "http_serde_ext::header_value::vec",
+        "http_serde_ext::header_value::vec_deque",
+        "http_serde_ext::header_value::linked_list",
+        "http_serde_ext::header_value::hash_map",
+        "http_serde_ext::header_value::btree_map"
+    );
+
+    for bytes in [&b"\x80"[..], b"a\xff", b"ab\xfe", b"\xe9\xe9\xe9\xe9"] {
+        let val = HeaderValue::from_bytes(bytes).unwrap();
+        no_intermediate_compare_roundtrip!(
+            HeaderValue,
+            val.clone(),
+            "http_serde_ext::header_value"
+        );
+    }
+
+    let mut map = HeaderMap::new();
+    map.append("content-disposition", latin1.clone());
+    map.append("content-disposition", utf8.clone());
+    map.insert("x-name", HeaderValue::from_static("plain"));
+    let json = json!({
+        "content-disposition": [
+            {"base64": "YXR0YWNobWVudDsgZmlsZW5hbWU9Y2Fm6S50eHQ="},
+            "café"
+        ],
+        "x-name": "plain"
+    });
+    serde_json_roundtrip!(HeaderMap, map.clone(), "http_serde_ext::header_map", json);
+    no_intermediate_compare_roundtrip!(HeaderMap, map.clone(), "http_serde_ext::header_map");
+
+    let mut map = HeaderMap::new();
+    map.insert("x-bytes", latin1);
+    serde_json_roundtrip!(
+        HeaderMap,
+        map,
+        "http_serde_ext::header_map",
+        json!({"x-bytes": {"base64": "YXR0YWNobWVudDsgZmlsZW5hbWU9Y2Fm6S50eHQ="}})
+    );
+
+    #[derive(Debug, Deserialize)]
+    struct Wrapper(
+        #[allow(dead_code)]
+        #[serde(with = "http_serde_ext::header_value")]
+        HeaderValue,
+    );
+
+    for (json, msg) in [
+        (json!({"base64": "!!!!"}), "invalid base64"),
+        (json!({"base64": "YWJj="}), "invalid base64"),
+        (
+            json!({"hex": "00"}),
+            "unknown field `hex`, expected one of `value`, `base64`, `sensitive`",
+        ),
+        (
+            json!({"base64": "YQ==", "value": "a"}),
+            "expected one of `value` or `base64`",
+        ),
+        (json!({}), "expected one of `value` or `base64`"),
+    ] {
+        let res = serde_json::from_value::<Wrapper>(json);
+        assert_eq!(res.unwrap_err().to_string(), msg);
+    }
+}
+
+#[test]
+fn test_header_value_sensitive() {
+    let mut secret = HeaderValue::from_static("Bearer secret");
+    secret.set_sensitive(true);
+    let mut binary = HeaderValue::from_bytes(b"\xff").unwrap();
+    binary.set_sensitive(true);
+
+    fn equate(a: &HeaderValue, b: &HeaderValue) {
+        assert_eq!(a, b);
+        assert_eq!(a.is_sensitive(), b.is_sensitive());
+    }
+
+    for val in [
+        secret.clone(),
+        binary.clone(),
+        HeaderValue::from_static("public"),
+    ] {
+        serde_cbor_roundtrip!(
+            HeaderValue,
+            val.clone(),
+            equate,
+            "http_serde_ext::header_value"
+        );
+        bincode_roundtrip!(
+            HeaderValue,
+            val.clone(),
+            equate,
+            "http_serde_ext::header_value"
+        );
+        postcard_roundtrip!(
+            HeaderValue,
+            val.clone(),
+            equate,
+            "http_serde_ext::header_value"
+        );
+        serde_yaml_no_intermediate_compare_roundtrip!(
+            HeaderValue,
+            val.clone(),
+            equate,
+            "http_serde_ext::header_value::with_sensitive"
+        );
+    }
+
+    serde_json_roundtrip!(
+        HeaderValue,
+        secret.clone(),
+        "http_serde_ext::header_value::with_sensitive",
+        json!({"value": "Bearer secret", "sensitive": true})
+    );
+    serde_json_roundtrip!(
+        HeaderValue,
+        binary.clone(),
+        "http_serde_ext::header_value::with_sensitive",
+        json!({"base64": "/w==", "sensitive": true})
+    );
+    serde_json_roundtrip!(
+        HeaderValue,
+        HeaderValue::from_static("public"),
+        "http_serde_ext::header_value::with_sensitive",
+        json!("public")
+    );
+
+    // The default representation drops the flag in human-readable formats.
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "http_serde_ext::header_value")] HeaderValue);
+
+    let ser = serde_json::to_value(Wrapper(secret.clone())).unwrap();
+    assert_eq!(ser, json!("Bearer secret"));
+    let de: Wrapper = serde_json::from_value(ser).unwrap();
+    assert!(!de.0.is_sensitive());
+    let de: Wrapper =
+        serde_json::from_value(json!({"value": "Bearer secret", "sensitive": true})).unwrap();
+    equate(&de.0, &secret);
+    let de: Wrapper = serde_json::from_value(json!({"value": "a", "sensitive": false})).unwrap();
+    assert!(!de.0.is_sensitive());
+
+    let mut map = HeaderMap::new();
+    map.insert("authorization", secret.clone());
+    map.append("cookie", HeaderValue::from_static("a=b"));
+    map.append("cookie", binary.clone());
+
+    fn equate_map(a: &HeaderMap, b: &HeaderMap) {
+        assert_eq!(a, b);
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert_eq!(a.1.is_sensitive(), b.1.is_sensitive(), "{}", a.0);
+        }
+    }
+
+    serde_json_roundtrip!(
+        HeaderMap,
+        map.clone(),
+        "http_serde_ext::header_map::with_sensitive",
+        json!({
+            "authorization": {"value": "Bearer secret", "sensitive": true},
+            "cookie": ["a=b", {"base64": "/w==", "sensitive": true}]
+        })
+    );
+    serde_yaml_no_intermediate_compare_roundtrip!(
+        HeaderMap,
+        map.clone(),
+        equate_map,
+        "http_serde_ext::header_map::with_sensitive"
+    );
+    serde_cbor_roundtrip!(
+        HeaderMap,
+        map.clone(),
+        equate_map,
+        "http_serde_ext::header_map"
+    );
+    bincode_roundtrip!(
+        HeaderMap,
+        map.clone(),
+        equate_map,
+        "http_serde_ext::header_map"
+    );
+    postcard_roundtrip!(
+        HeaderMap,
+        map.clone(),
+        equate_map,
+        "http_serde_ext::header_map"
+    );
+
+    let mut request = Request::new(());
+    *request.headers_mut() = map.clone();
+
+    #[derive(Serialize, Deserialize)]
+    struct RequestWrapper(#[serde(with = "http_serde_ext::request::with_sensitive")] Request<()>);
+
+    let ser = serde_json::to_value(RequestWrapper(request.clone())).unwrap();
+    assert_eq!(
+        ser["head"]["headers"]["authorization"],
+        json!({"value": "Bearer secret", "sensitive": true})
+    );
+    let de: RequestWrapper = serde_json::from_value(ser).unwrap();
+    equate_map(de.0.headers(), &map);
+
+    #[derive(Serialize, Deserialize)]
+    struct DefaultRequestWrapper(#[serde(with = "http_serde_ext::request")] Request<()>);
+
+    let ser = bincode::serialize(&DefaultRequestWrapper(request)).unwrap();
+    let de: DefaultRequestWrapper = bincode::deserialize(&ser).unwrap();
+    equate_map(de.0.headers(), &map);
+
+    let mut response = Response::new(());
+    *response.headers_mut() = map.clone();
+
+    #[derive(Serialize, Deserialize)]
+    struct ResponseWrapper(
+        #[serde(with = "http_serde_ext::response::with_sensitive")] Response<()>,
+    );
+
+    let ser = serde_yaml::to_string(&ResponseWrapper(response)).unwrap();
+    let de: ResponseWrapper = serde_yaml::from_str(&ser).unwrap();
+    equate_map(de.0.headers(), &map);
+}
+
+#[test]
+fn test_header_value_structured() {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct List(#[serde(with = "http_serde_ext::header_value::structured::list")] HeaderValue);
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Dictionary(
+        #[serde(with = "http_serde_ext::header_value::structured::dictionary")] HeaderValue,
+    );
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Item(#[serde(with = "http_serde_ext::header_value::structured::item")] HeaderValue);
+
+    let list =
+        HeaderValue::from_static(r#"sugar, tea;q=0.5, (rum "dark" :aGk=:);lvl=2, -7, ?0, 1.125"#);
+    let ser = serde_json::to_value(List(list.clone())).unwrap();
+    assert_eq!(
+        ser,
+        json!([
+            {"token": "sugar"},
+            {"value": {"token": "tea"}, "params": {"q": 0.5}},
+            {"items": [{"token": "rum"}, "dark", {"bytes": "aGk="}], "params": {"lvl": 2}},
+            -7,
+            false,
+            1.125
+        ])
+    );
+    let de: List = serde_json::from_value(ser).unwrap();
+    assert_eq!(de.0, list);
+    no_intermediate_compare_roundtrip!(
+        HeaderValue,
+        list.clone(),
+        "http_serde_ext::header_value::structured::list"
+    );
+
+    let dictionary = HeaderValue::from_static("u=5, i, a=(1 2), b;x");
+    let ser = serde_json::to_string(&Dictionary(dictionary.clone())).unwrap();
+    assert_eq!(
+        ser,
+        r#"{"u":5,"i":true,"a":[1,2],"b":{"value":true,"params":{"x":true}}}"#
+    );
+    let de: Dictionary = serde_json::from_str(&ser).unwrap();
+    assert_eq!(de.0, dictionary);
+
+    let de: Dictionary = serde_json::from_str(r#"{"u":1.50,"i":false,"c":"a \"b\""}"#).unwrap();
+    assert_eq!(de.0, r#"u=1.5, i=?0, c="a \"b\"""#);
+    let ser = serde_json::to_string(&Dictionary(HeaderValue::from_static("u=5,   i"))).unwrap();
+    let de: Dictionary = serde_json::from_str(&ser).unwrap();
+    assert_eq!(de.0, "u=5, i");
+
+    for (text, value) in [
+        (r#""Linux""#, json!("Linux")),
+        ("?1", json!(true)),
+        ("-42", json!(-42)),
+        ("2.0", json!(2.0)),
+        (
+            "*foo/bar;v=1",
+            json!({"value": {"token": "*foo/bar"}, "params": {"v": 1}}),
+        ),
+    ] {
+        let item = HeaderValue::from_static(text);
+        let ser = serde_json::to_value(Item(item.clone())).unwrap();
+        assert_eq!(ser, value);
+        let de: Item = serde_json::from_value(ser).unwrap();
+        assert_eq!(de.0, item);
+    }
+
+    let invalid = HeaderValue::from_static("a,,b");
+    serde_json_roundtrip!(
+        HeaderValue,
+        invalid.clone(),
+        "http_serde_ext::header_value::structured::list",
+        json!({"$raw": "a,,b"})
+    );
+    let invalid = HeaderValue::from_bytes(b"caf\xe9").unwrap();
+    let ser = serde_json::to_value(Item(invalid.clone())).unwrap();
+    assert_eq!(ser, json!({"$raw": {"base64": "Y2Fm6Q=="}}));
+    let de: Item = serde_json::from_value(ser).unwrap();
+    assert_eq!(de.0, invalid);
+
+    for (json, err) in [
+        (json!({"token": "a b"}), "invalid token"),
+        (json!({"bytes": "a"}), "invalid base64"),
+        (json!({"token": "a", "bytes": "YQ=="}), "expected one of `token`, `bytes`, `value` or `items`"),
+        (json!({"foo": 1}), "unknown field `foo`, expected one of `token`, `bytes`, `value`, `params`, `items`, `$raw`"),
+        (json!(1_000_000_000_000_000_u64), "invalid value: integer `1000000000000000`, expected a structured field item"),
+        (json!(i64::MIN), "invalid value: integer `-9223372036854775808`, expected a structured field item"),
+        (json!([1]), "invalid type: sequence, expected a structured field item"),
+    ] {
+        let res = serde_json::from_value::<Item>(json);
+        assert_eq!(res.unwrap_err().to_string(), err);
+    }
+    let res = serde_json::from_value::<List>(json!(1));
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "invalid type: a bare item, expected a structured field list"
+    );
+    let res = serde_json::from_value::<Dictionary>(json!({"A": 1}));
+    assert_eq!(res.unwrap_err().to_string(), "invalid key");
+
+    let mut map = HeaderMap::new();
+    map.insert("priority", HeaderValue::from_static("u=3"));
+    map.insert(
+        "sec-ch-ua",
+        HeaderValue::from_static(r#""Chromium";v="118""#),
+    );
+    map.append("accept-ch", HeaderValue::from_static("sec-ch-ua-model"));
+    map.append("accept-ch", HeaderValue::from_static("sec-ch-ua-arch"));
+    map.insert("sec-ch-ua-mobile", HeaderValue::from_static("yes, no"));
+    map.insert("x-custom", HeaderValue::from_static("?1"));
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Headers(#[serde(with = "http_serde_ext::header_map::structured")] HeaderMap);
+
+    let ser = serde_json::to_value(Headers(map.clone())).unwrap();
+    assert_eq!(
+        ser,
+        json!({
+            "priority": {"u": 3},
+            "sec-ch-ua": [{"value": "Chromium", "params": {"v": "118"}}],
+            "accept-ch": {"$raw": ["sec-ch-ua-model", "sec-ch-ua-arch"]},
+            "sec-ch-ua-mobile": {"$raw": "yes, no"},
+            "x-custom": "?1"
+        })
+    );
+    let de: Headers = serde_json::from_value(ser).unwrap();
+    assert_eq!(de.0, map);
+    no_intermediate_compare_roundtrip!(
+        HeaderMap,
+        map.clone(),
+        "http_serde_ext::header_map::structured"
+    );
+    let res = serde_json::from_value::<Headers>(json!({"priority": {"u": i64::MIN}}));
+    assert!(res.is_err());
+    let res = serde_json::from_value::<Headers>(json!({"priority": {"$raw": [1]}}));
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "invalid type: integer `1`, expected a value for header priority"
+    );
+
+    // Headers the crate doesn't know can be added to the list.
+    use http_serde_ext::StructuredKind;
+    let known = [
+        (HeaderName::from_static("x-custom"), StructuredKind::Item),
+        (HeaderName::from_static("priority"), StructuredKind::Item),
+    ];
+    let ser = http_serde_ext::header_map::structured::serialize_with_known(
+        &map,
+        &known,
+        serde_json::value::Serializer,
+    )
+    .unwrap();
+    assert_eq!(ser["x-custom"], json!(true));
+    assert_eq!(ser["priority"], json!({"$raw": "u=3"}));
+    let de = http_serde_ext::header_map::structured::deserialize_with_known(ser, &known).unwrap();
+    assert_eq!(de, map);
+}
+
+#[test]
+fn test_header_map_cookies() {
+    let mut map = HeaderMap::new();
+    map.append(
+        "set-cookie",
+        HeaderValue::from_static("id=a3fWa; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Secure"),
+    );
+    map.append("set-cookie", HeaderValue::from_static("lang=en"));
+    map.append("set-cookie", HeaderValue::from_static("broken;Path=/"));
+    map.insert("cookie", HeaderValue::from_static("a=1; b=; a=2"));
+    map.insert("accept", HeaderValue::from_static("*/*"));
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Headers(#[serde(with = "http_serde_ext::header_map::cookies")] HeaderMap);
+
+    let ser = serde_json::to_value(Headers(map.clone())).unwrap();
+    assert_eq!(
+        ser,
+        json!({
+            "set-cookie": [
+                {
+                    "name": "id",
+                    "value": "a3fWa",
+                    "attributes": {"Expires": "Wed, 21 Oct 2015 07:28:00 GMT", "Secure": true}
+                },
+                {"name": "lang", "value": "en"},
+                "broken;Path=/"
+            ],
+            "cookie": [
+                {"name": "a", "value": "1"},
+                {"name": "b", "value": ""},
+                {"name": "a", "value": "2"}
+            ],
+            "accept": "*/*"
+        })
+    );
+    let de: Headers = serde_json::from_value(ser).unwrap();
+    assert_eq!(de.0, map);
+    no_intermediate_compare_roundtrip!(
+        HeaderMap,
+        map.clone(),
+        "http_serde_ext::header_map::cookies"
+    );
+
+    let ser = serde_json::to_string(&Headers(map.clone())).unwrap();
+    assert!(ser.contains(r#"{"Expires":"Wed, 21 Oct 2015 07:28:00 GMT","Secure":true}"#));
+    let de: Headers = serde_json::from_str(&ser).unwrap();
+    assert_eq!(de.0, map);
+
+    let mut repeated = HeaderMap::new();
+    repeated.insert(
+        "set-cookie",
+        HeaderValue::from_static("id=1; Path=/a; Path=/b; Secure"),
+    );
+    roundtrip!(
+        HeaderMap,
+        repeated.clone(),
+        "http_serde_ext::header_map::cookies",
+        json!({"set-cookie": "id=1; Path=/a; Path=/b; Secure"}),
+        "set-cookie: id=1; Path=/a; Path=/b; Secure\n"
+    );
+
+    let mut lines = HeaderMap::new();
+    lines.append("cookie", HeaderValue::from_static("a=1; b=2"));
+    lines.append("cookie", HeaderValue::from_static("c=3"));
+    lines.append("cookie", HeaderValue::from_static("d;e"));
+    lines.append("cookie", HeaderValue::from_bytes(b"f=\xff").unwrap());
+    lines.append("cookie", HeaderValue::from_static(""));
+    lines.insert("set-cookie", HeaderValue::from_bytes(b"g=\xff").unwrap());
+    serde_json_roundtrip!(
+        HeaderMap,
+        lines.clone(),
+        "http_serde_ext::header_map::cookies",
+        json!({
+            "cookie": [
+                [{"name": "a", "value": "1"}, {"name": "b", "value": "2"}],
+                [{"name": "c", "value": "3"}],
+                "d;e",
+                {"base64": "Zj3/"},
+                []
+            ],
+            "set-cookie": {"base64": "Zz3/"}
+        })
+    );
+    no_intermediate_compare_roundtrip!(
+        HeaderMap,
+        lines.clone(),
+        "http_serde_ext::header_map::cookies"
+    );
+
+    for (json, err) in [
+        (
+            json!({"cookie": [{"name": "a", "value": "1"}, "b=2"]}),
+            "expected either cookie pairs or cookie header values",
+        ),
+        (
+            json!({"cookie": {"name": "a", "value": "1"}}),
+            "invalid type: map, expected cookie header values",
+        ),
+        (
+            json!({"set-cookie": {"name": "a", "value": "1; Path=/"}}),
+            "invalid cookie a",
+        ),
+        (
+            json!({"set-cookie": {"name": "a=b", "value": "c"}}),
+            "invalid cookie a=b",
+        ),
+        (
+            json!({"cookie": [{"name": "a b", "value": "c"}]}),
+            "invalid cookie a b",
+        ),
+        (
+            json!({"set-cookie": {"name": "a", "value": "1", "attributes": {"Max=Age": "1"}}}),
+            "invalid cookie a",
+        ),
+        (
+            json!({"set-cookie": {"name": "a", "value": "1", "attributes": {"Secure": false}}}),
+            "invalid value: boolean `false`, expected a string or `true`",
+        ),
+        (
+            json!({"cookie": [{"name": "a", "value": "1", "attributes": {}}]}),
+            "unknown field `attributes`, expected one of `name`, `value`, `base64`",
+        ),
+        (
+            json!({"set-cookie": {"name": "a"}}),
+            "expected `name` and `value`, or `base64`",
+        ),
+    ] {
+        let res = serde_json::from_value::<Headers>(json);
+        assert_eq!(res.unwrap_err().to_string(), err);
+    }
+
+    let mut request = Request::new(());
+    *request.headers_mut() = map.clone();
+
+    #[derive(Serialize, Deserialize)]
+    struct RequestWrapper(#[serde(with = "http_serde_ext::request::cookies")] Request<()>);
+
+    let ser = serde_json::to_value(RequestWrapper(request.clone())).unwrap();
+    assert_eq!(
+        ser["head"]["headers"]["cookie"][2],
+        json!({"name": "a", "value": "2"})
+    );
+    let de: RequestWrapper = serde_json::from_value(ser).unwrap();
+    assert_eq!(de.0.headers(), &map);
+
+    #[derive(Serialize, Deserialize)]
+    struct ResponseWrapper(#[serde(with = "http_serde_ext::response::cookies")] Response<()>);
+
+    let mut response = Response::new(());
+    *response.headers_mut() = map.clone();
+    let ser = serde_yaml::to_string(&ResponseWrapper(response)).unwrap();
+    assert!(ser.contains("name: lang"));
+    let de: ResponseWrapper = serde_yaml::from_str(&ser).unwrap();
+    assert_eq!(de.0.headers(), &map);
+
+    let mut response = Response::new(());
+    *response.headers_mut() = map.clone();
+    let ser = bincode::serialize(&ResponseWrapper(response)).unwrap();
+    let de: ResponseWrapper = bincode::deserialize(&ser).unwrap();
+    assert_eq!(de.0.headers(), &map);
+}
+
+#[test]
+fn test_header_map_duplicates() {
+    use http::header::ACCEPT;
+    use http_serde_ext::{DuplicatePolicy, HeaderCaseMap};
+
+    #[derive(Debug, Deserialize)]
+    struct Error(#[serde(with = "http_serde_ext::header_map::duplicate::error")] HeaderMap);
+    #[derive(Debug, Deserialize)]
+    struct Append(#[serde(with = "http_serde_ext::header_map::duplicate::append")] HeaderMap);
+    #[derive(Debug, Deserialize)]
+    struct LastWins(#[serde(with = "http_serde_ext::header_map::duplicate::last_wins")] HeaderMap);
+    #[derive(Debug, Deserialize)]
+    struct FirstWins(
+        #[serde(with = "http_serde_ext::header_map::duplicate::first_wins")] HeaderMap,
+    );
+    #[derive(Debug, Deserialize)]
+    struct Default(#[serde(with = "http_serde_ext::header_map")] HeaderMap);
+
+    fn values(map: &HeaderMap) -> Vec<&str> {
+        map.get_all("accept")
+            .iter()
+            .map(|v| v.to_str().unwrap())
+            .collect()
+    }
+
+    let yaml = "Accept: [text/html, text/plain]\nx-a: b\naccept: '*/*'\n";
+    let res = serde_yaml::from_str::<Error>(yaml);
+    assert_eq!(res.unwrap_err().to_string(), "duplicate header accept");
+    let de: Error = serde_yaml::from_str("accept: '*/*'\n").unwrap();
+    assert_eq!(values(&de.0), ["*/*"]);
+    let de: Append = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(values(&de.0), ["text/html", "text/plain", "*/*"]);
+    let de: LastWins = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(values(&de.0), ["*/*"]);
+    let de: FirstWins = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(values(&de.0), ["text/html", "text/plain"]);
+    let de: Default = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(values(&de.0), ["text/html", "text/plain", "*/*"]);
+    assert_eq!(de.0["x-a"], "b");
+
+    let json = r#"{"accept":"text/html","Accept":["text/plain","*/*"]}"#;
+    let de: LastWins = serde_json::from_str(json).unwrap();
+    assert_eq!(values(&de.0), ["text/plain", "*/*"]);
+    let de: Default = serde_json::from_str(json).unwrap();
+    assert_eq!(values(&de.0), ["text/html", "text/plain", "*/*"]);
+
+    let mut de = serde_json::Deserializer::from_str(json);
+    let map = DuplicatePolicy::FirstWins
+        .deserialize_header_map(&mut de)
+        .unwrap();
+    assert_eq!(values(&map), ["text/html"]);
+
+    let mut de = serde_json::Deserializer::from_str(r#"{"accept":1,"ACCEPT":2}"#);
+    let res = DuplicatePolicy::Error.deserialize_header_map_generic::<_, u8>(&mut de);
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "duplicate header accept at line 1 column 23"
+    );
+
+    #[derive(Debug, Deserialize)]
+    struct Generic(
+        #[serde(with = "http_serde_ext::header_map_generic::duplicate::last_wins")] HeaderMap<u8>,
+    );
+
+    let de: Generic = serde_json::from_str(r#"{"accept":[1,2],"ACCEPT":3}"#).unwrap();
+    assert_eq!(de.0.get_all("accept").iter().collect::<Vec<_>>(), [&3]);
+
+    // Binary formats decode the values straight into the map.
+    type Raw<'a> = Vec<(&'a str, Vec<(&'a [u8], bool)>)>;
+    let raw: Raw = vec![
+        (
+            "accept",
+            vec![(b"text/html", false), (b"text/plain", false)],
+        ),
+        ("x-a", vec![(b"b", true)]),
+        ("accept", vec![(b"*/*", false)]),
+    ];
+    let ser = bincode::serialize(&raw).unwrap();
+    let res = bincode::deserialize::<Error>(&ser);
+    assert_eq!(res.unwrap_err().to_string(), "duplicate header accept");
+    let de: Append = bincode::deserialize(&ser).unwrap();
+    assert_eq!(values(&de.0), ["text/html", "text/plain", "*/*"]);
+    assert!(de.0["x-a"].is_sensitive());
+    let de: LastWins = bincode::deserialize(&ser).unwrap();
+    assert_eq!(values(&de.0), ["*/*"]);
+    let de: FirstWins = bincode::deserialize(&ser).unwrap();
+    assert_eq!(values(&de.0), ["text/html", "text/plain"]);
+    assert_eq!(de.0["x-a"], "b");
+
+    let raw: Raw = vec![("x-a", vec![])];
+    let ser = bincode::serialize(&raw).unwrap();
+    let res = bincode::deserialize::<Default>(&ser);
+    assert_eq!(res.unwrap_err().to_string(), "no value for header x-a");
+
+    let raw: Vec<(&str, Vec<u8>)> = vec![("accept", vec![1, 2]), ("ACCEPT", vec![3])];
+    let ser = postcard::to_allocvec(&raw).unwrap();
+    let de: Generic = postcard::from_bytes(&ser).unwrap();
+    assert_eq!(de.0.get_all("accept").iter().collect::<Vec<_>>(), [&3]);
+
+    #[derive(Deserialize)]
+    struct CookiesError(
+        #[serde(with = "http_serde_ext::header_map::cookies::duplicate::error")] HeaderMap,
+    );
+    #[derive(Debug, Deserialize)]
+    struct CookiesFirstWins(
+        #[serde(with = "http_serde_ext::header_map::cookies::duplicate::first_wins")] HeaderMap,
+    );
+    #[derive(Debug, Deserialize)]
+    struct StructuredLastWins(
+        #[serde(with = "http_serde_ext::header_map::structured::duplicate::last_wins")] HeaderMap,
+    );
+    #[derive(Deserialize)]
+    struct CaseError(
+        #[serde(with = "http_serde_ext::header_map::original_case::duplicate::error")]
+        (HeaderMap, HeaderCaseMap),
+    );
+    #[derive(Debug, Deserialize)]
+    struct CaseLastWins(
+        #[serde(with = "http_serde_ext::header_map::original_case::duplicate::last_wins")]
+        (HeaderMap, HeaderCaseMap),
+    );
+    #[derive(Debug, Deserialize)]
+    struct CaseFirstWins(
+        #[serde(with = "http_serde_ext::header_map::original_case::duplicate::first_wins")]
+        (HeaderMap, HeaderCaseMap),
+    );
+
+    let res = serde_yaml::from_str::<CookiesError>(yaml);
+    assert_eq!(res.err().unwrap().to_string(), "duplicate header accept");
+    let de: CookiesError = serde_yaml::from_str("cookie: a=1\n").unwrap();
+    assert_eq!(de.0["cookie"], "a=1");
+    let de: CookiesFirstWins = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(values(&de.0), ["text/html", "text/plain"]);
+    let de: StructuredLastWins = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(values(&de.0), ["*/*"]);
+    let raw: Raw = vec![
+        ("accept", vec![(b"a", false)]),
+        ("Accept", vec![(b"b", false)]),
+    ];
+    let ser = bincode::serialize(&raw).unwrap();
+    let res = bincode::deserialize::<CookiesError>(&ser);
+    assert_eq!(res.err().unwrap().to_string(), "duplicate header accept");
+
+    let res = serde_yaml::from_str::<CaseError>(yaml);
+    assert_eq!(res.err().unwrap().to_string(), "duplicate header accept");
+    let de: CaseError = serde_yaml::from_str("Accept: '*/*'\n").unwrap();
+    assert_eq!(values(&de.0 .0), ["*/*"]);
+    let de: CaseLastWins = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(values(&de.0 .0), ["*/*"]);
+    assert_eq!(de.0 .1.get_all(&ACCEPT).collect::<Vec<_>>(), ["accept"]);
+    let de: CaseFirstWins = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(values(&de.0 .0), ["text/html", "text/plain"]);
+    assert_eq!(
+        de.0 .1.get_all(&ACCEPT).collect::<Vec<_>>(),
+        ["Accept", "Accept"]
+    );
+
+    // Values a policy ignores are still consumed in binary formats.
+    let raw: Raw = vec![
+        ("accept", vec![(b"a", false)]),
+        ("Accept", vec![(b"b", false), (b"c", false)]),
+        ("X-A", vec![(b"d", false)]),
+    ];
+    let ser = bincode::serialize(&raw).unwrap();
+    let res = bincode::deserialize::<CaseError>(&ser);
+    assert_eq!(res.err().unwrap().to_string(), "duplicate header accept");
+    let de: CaseLastWins = bincode::deserialize(&ser).unwrap();
+    assert_eq!(values(&de.0 .0), ["b", "c"]);
+    assert_eq!(
+        de.0 .1.get_all(&ACCEPT).collect::<Vec<_>>(),
+        ["Accept", "Accept"]
+    );
+    let de: CaseFirstWins = bincode::deserialize(&ser).unwrap();
+    assert_eq!(values(&de.0 .0), ["a"]);
+    assert_eq!(de.0 .1.get_all(&ACCEPT).collect::<Vec<_>>(), ["accept"]);
+    assert_eq!(de.0 .0["x-a"], "d");
+    let raw: Raw = vec![("X-A", vec![])];
+    let ser = bincode::serialize(&raw).unwrap();
+    let res = bincode::deserialize::<CaseFirstWins>(&ser);
+    assert_eq!(res.err().unwrap().to_string(), "no value for header X-A");
+
+    let policy: DuplicatePolicy = serde_json::from_str(r#""last_wins""#).unwrap();
+    assert_eq!(policy, DuplicatePolicy::LastWins);
+    assert_eq!(DuplicatePolicy::default(), DuplicatePolicy::Append);
+}
+
+#[test]
+fn test_limits() {
+    use http_serde_ext::{HeaderCaseMap, Limits};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Headers(#[serde(with = "http_serde_ext::header_map")] HeaderMap);
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Generic(#[serde(with = "http_serde_ext::header_map_generic")] HeaderMap<u8>);
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Pairs(#[serde(with = "http_serde_ext::header_map::pairs")] HeaderMap);
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Values(#[serde(with = "http_serde_ext::header_value::vec")] Vec<HeaderValue>);
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Uris(#[serde(with = "http_serde_ext::uri::vec")] Vec<Uri>);
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Cased(
+        #[serde(with = "http_serde_ext::header_map::original_case")] (HeaderMap, HeaderCaseMap),
+    );
+
+    let mut map = HeaderMap::new();
+    map.append("accept", HeaderValue::from_static("text/html"));
+    map.append("accept", HeaderValue::from_static("*/*"));
+    map.append("host", HeaderValue::from_static("example.com"));
+
+    let limits = Limits::new().max_headers(2);
+    let json = serde_json::to_string(&Headers(map.clone())).unwrap();
+    let res = limits.scope(|| serde_json::from_str::<Headers>(&json));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("more than 2 headers"));
+    let ser = bincode::serialize(&Headers(map.clone())).unwrap();
+    let res = limits.scope(|| bincode::deserialize::<Headers>(&ser));
+    assert_eq!(res.unwrap_err().to_string(), "more than 2 headers");
+    let json = serde_json::to_string(&Pairs(map.clone())).unwrap();
+    let res = limits.scope(|| serde_json::from_str::<Pairs>(&json));
+    assert!(res.is_err());
+    let res = limits.scope(|| serde_json::from_str::<Generic>(r#"{"a":[1,2],"b":3}"#));
+    assert!(res.is_err());
+    let de = limits.scope(|| serde_json::from_str::<Generic>(r#"{"a":[1,2]}"#));
+    assert_eq!(de.unwrap().0.len(), 2);
+
+    let limits = Limits::new().max_header_value_len(9);
+    let json = serde_json::to_string(&Headers(map.clone())).unwrap();
+    let res = limits.scope(|| serde_json::from_str::<Headers>(&json));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("invalid value for header host: header value is longer than 9 bytes"));
+    let ser = bincode::serialize(&Headers(map.clone())).unwrap();
+    assert!(limits
+        .scope(|| bincode::deserialize::<Headers>(&ser))
+        .is_err());
+    let res = limits.scope(|| serde_json::from_str::<Values>(r#"["0123456789"]"#));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("header value is longer than 9 bytes"));
+    let de = limits.scope(|| serde_json::from_str::<Values>(r#"["012345678"]"#));
+    assert_eq!(de.unwrap().0.len(), 1);
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Cookies(#[serde(with = "http_serde_ext::header_map::cookies")] HeaderMap);
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Structured(#[serde(with = "http_serde_ext::header_map::structured")] HeaderMap);
+
+    let mut cookies = HeaderMap::new();
+    cookies.insert("cookie", HeaderValue::from_static("a=1; b=2"));
+    cookies.insert("set-cookie", HeaderValue::from_static("id=1; Secure"));
+    cookies.append("set-cookie", HeaderValue::from_bytes(b"\xff").unwrap());
+    let json = serde_json::to_string(&Cookies(cookies.clone())).unwrap();
+    let res = Limits::new()
+        .max_header_value_len(7)
+        .scope(|| serde_json::from_str::<Cookies>(&json));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("header value is longer than 7 bytes"));
+    let res = Limits::new()
+        .max_header_value_len(0)
+        .scope(|| serde_json::from_str::<Cookies>(r#"{"set-cookie":{"base64":"/w=="}}"#));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("header value is longer than 0 bytes"));
+    let de = Limits::new()
+        .max_header_value_len(12)
+        .scope(|| serde_json::from_str::<Cookies>(&json));
+    assert_eq!(de.unwrap().0, cookies);
+
+    let json = r#"{"priority":{"u":3,"i":true}}"#;
+    let res = Limits::new()
+        .max_header_value_len(5)
+        .scope(|| serde_json::from_str::<Structured>(json));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("header value is longer than 5 bytes"));
+    let de = Limits::new()
+        .max_header_value_len(6)
+        .scope(|| serde_json::from_str::<Structured>(json));
+    assert_eq!(de.unwrap().0["priority"], "u=3, i");
+
+    // `HeaderMap` can't hold this many names, whatever the limits allow.
+    let names = (0..40_000).map(|i| format!("x-{i}")).collect::<Vec<_>>();
+    let limits = Limits::new().max_headers(100_000);
+    let json = serde_json::to_string(
+        &names
+            .iter()
+            .map(|name| (name.as_str(), "1"))
+            .collect::<BTreeMap<_, _>>(),
+    )
+    .unwrap();
+    let res = limits.scope(|| serde_json::from_str::<Headers>(&json));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("too many header names for a header map"));
+    let res = limits.scope(|| serde_json::from_str::<Generic>(&json.replace("\"1\"", "1")));
+    assert!(res.is_err());
+    let res = limits.scope(|| serde_json::from_str::<Cased>(&json));
+    assert!(res.is_err());
+    let pairs = names
+        .iter()
+        .map(|name| json!({"name": name, "value": "1"}))
+        .collect::<Vec<_>>();
+    let res = limits.scope(|| serde_json::from_value::<Pairs>(json!(pairs)));
+    assert!(res.is_err());
+
+    let limits = Limits::new().max_uri_len(10);
+    let res = limits.scope(|| serde_json::from_str::<Uris>(r#"["https://example.com/"]"#));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("uri is longer than 10 bytes"));
+    let de = limits.scope(|| serde_json::from_str::<Uris>(r#"["/a/b/c"]"#));
+    assert_eq!(de.unwrap().0, [Uri::from_static("/a/b/c")]);
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Inner(#[serde(with = "http_serde_ext::request")] Request<()>);
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Outer(#[serde(with = "http_serde_ext::request")] Request<Inner>);
+
+    let nested = Outer(Request::new(Inner(Request::new(()))));
+    let json = serde_json::to_string(&nested).unwrap();
+    let res = Limits::new()
+        .max_depth(1)
+        .scope(|| serde_json::from_str::<Outer>(&json));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("requests and responses are nested more than 1 deep"));
+    // The failure above doesn't leave the depth counted.
+    let de = Limits::new()
+        .max_depth(2)
+        .scope(|| serde_json::from_str::<Outer>(&json));
+    assert!(de.is_ok());
+
+    // Lengths claimed by the input don't preallocate.
+    let ser = bincode::serialize(&u64::MAX).unwrap();
+    assert!(bincode::deserialize::<Values>(&ser).is_err());
+    assert!(bincode::deserialize::<Headers>(&ser).is_err());
+    assert!(bincode::deserialize::<Generic>(&ser).is_err());
+    let ser = bincode::serialize(&(1u64 << 20)).unwrap();
+    let res = Limits::new()
+        .max_preallocation(usize::MAX)
+        .scope(|| bincode::deserialize::<Headers>(&ser));
+    assert!(res.is_err());
+
+    let limits = Limits::new().max_headers(1).max_uri_len(2);
+    assert_eq!(Limits::current(), Limits::default());
+    limits.scope(|| {
+        assert_eq!(Limits::current(), limits);
+        Limits::new().scope(|| assert_eq!(Limits::current(), Limits::new()));
+        assert_eq!(Limits::current(), limits);
+    });
+    assert_eq!(Limits::current(), Limits::new());
+}
+
+#[test]
+fn test_merge_into() {
+    use http_serde_ext::{header_map::MergeInto, DuplicatePolicy};
+    use serde::de::DeserializeSeed;
+
+    let mut defaults = HeaderMap::new();
+    defaults.append("accept", HeaderValue::from_static("*/*"));
+    defaults.append("x-env", HeaderValue::from_static("dev"));
+
+    let json = r#"{"x-env":"prod","x-region":["eu","us"]}"#;
+    let merge = |policy| {
+        let mut headers = defaults.clone();
+        let mut de = serde_json::Deserializer::from_str(json);
+        MergeInto::new(&mut headers)
+            .policy(policy)
+            .deserialize(&mut de)
+            .map(|_| headers)
+    };
+    let headers = merge(DuplicatePolicy::Append).unwrap();
+    assert_eq!(
+        headers.get_all("x-env").iter().collect::<Vec<_>>(),
+        ["dev", "prod"]
+    );
+    assert_eq!(
+        headers.get_all("x-region").iter().collect::<Vec<_>>(),
+        ["eu", "us"]
+    );
+    assert_eq!(headers["accept"], "*/*");
+    let headers = merge(DuplicatePolicy::LastWins).unwrap();
+    assert_eq!(
+        headers.get_all("x-env").iter().collect::<Vec<_>>(),
+        ["prod"]
+    );
+    let headers = merge(DuplicatePolicy::FirstWins).unwrap();
+    assert_eq!(headers.get_all("x-env").iter().collect::<Vec<_>>(), ["dev"]);
+    assert_eq!(headers.len(), 4);
+    assert!(merge(DuplicatePolicy::Error)
+        .unwrap_err()
+        .to_string()
+        .starts_with("duplicate header x-env"));
+
+    #[derive(Serialize)]
+    struct Headers(#[serde(with = "http_serde_ext::header_map")] HeaderMap);
+
+    let mut layer = HeaderMap::new();
+    layer.append("x-env", HeaderValue::from_static("prod"));
+    let ser = postcard::to_allocvec(&Headers(layer)).unwrap();
+    let mut headers = defaults.clone();
+    MergeInto::new(&mut headers)
+        .policy(DuplicatePolicy::LastWins)
+        .deserialize(&mut postcard::Deserializer::from_bytes(&ser))
+        .unwrap();
+    assert_eq!(headers["x-env"], "prod");
+    assert_eq!(headers["accept"], "*/*");
+
+    #[derive(Serialize, Deserialize)]
+    struct RequestWrapper(#[serde(with = "http_serde_ext::request")] Request<String>);
+
+    let template = Request::builder()
+        .method(Method::GET)
+        .uri("https://example.com/")
+        .header("user-agent", "app/1.0")
+        .header("accept", "*/*")
+        .extension(7u8)
+        .body("template".to_owned())
+        .unwrap();
+
+    let mut request = template.clone();
+    let json = r#"{"head":{"uri":"/health","headers":{"user-agent":"app/2.0"}}}"#;
+    http_serde_ext::request::MergeInto::new(&mut request)
+        .policy(DuplicatePolicy::LastWins)
+        .deserialize(&mut serde_json::Deserializer::from_str(json))
+        .unwrap();
+    assert_eq!(request.method(), Method::GET);
+    assert_eq!(request.uri(), "/health");
+    assert_eq!(request.headers()["user-agent"], "app/2.0");
+    assert_eq!(request.headers()["accept"], "*/*");
+    assert_eq!(request.body(), "template");
+    assert_eq!(request.extensions().get::<u8>(), Some(&7));
+
+    let mut request = template.clone();
+    let res = http_serde_ext::request::MergeInto::new(&mut request)
+        .policy(DuplicatePolicy::Error)
+        .deserialize(&mut serde_json::Deserializer::from_str(json));
+    assert!(res.is_err());
+    assert_eq!(request.uri(), "https://example.com/");
+
+    // Names repeated in the input follow the policy too.
+    let json = r#"{"head":{"headers":{"x-a":"1","x-a":"2"}}}"#;
+    let merge = |policy| {
+        let mut request = template.clone();
+        http_serde_ext::request::MergeInto::new(&mut request)
+            .policy(policy)
+            .deserialize(&mut serde_json::Deserializer::from_str(json))
+            .map(|_| request)
+    };
+    let request = merge(DuplicatePolicy::LastWins).unwrap();
+    assert_eq!(
+        request.headers().get_all("x-a").iter().collect::<Vec<_>>(),
+        ["2"]
+    );
+    let request = merge(DuplicatePolicy::FirstWins).unwrap();
+    assert_eq!(
+        request.headers().get_all("x-a").iter().collect::<Vec<_>>(),
+        ["1"]
+    );
+    let request = merge(DuplicatePolicy::Append).unwrap();
+    assert_eq!(request.headers().get_all("x-a").iter().count(), 2);
+    assert!(merge(DuplicatePolicy::Error)
+        .unwrap_err()
+        .to_string()
+        .starts_with("duplicate header x-a"));
+
+    // A limit tripped partway through the headers leaves them unchanged.
+    let mut request = template.clone();
+    let json = r#"{"head":{"method":"PUT","headers":{"x-a":"1","x-b":"2"}}}"#;
+    let res = http_serde_ext::Limits::new().max_headers(3).scope(|| {
+        http_serde_ext::request::MergeInto::new(&mut request)
+            .deserialize(&mut serde_json::Deserializer::from_str(json))
+    });
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("more than 3 headers"));
+    assert_eq!(request.method(), Method::GET);
+    assert_eq!(request.headers(), template.headers());
+
+    // The body may borrow from the input.
+    let mut request = Request::new("");
+    let json = r#"{"body":"borrowed"}"#;
+    http_serde_ext::request::MergeInto::new(&mut request)
+        .deserialize(&mut serde_json::Deserializer::from_str(json))
+        .unwrap();
+    assert_eq!(*request.body(), "borrowed");
+
+    let mut layer = Request::builder()
+        .method(Method::POST)
+        .uri("/submit")
+        .header("content-type", "text/plain")
+        .body("hello".to_owned())
+        .unwrap();
+    *layer.version_mut() = Version::HTTP_2;
+    let ser = postcard::to_allocvec(&RequestWrapper(layer)).unwrap();
+    let mut request = template.clone();
+    http_serde_ext::request::MergeInto::new(&mut request)
+        .deserialize(&mut postcard::Deserializer::from_bytes(&ser))
+        .unwrap();
+    assert_eq!(request.method(), Method::POST);
+    assert_eq!(request.uri(), "/submit");
+    assert_eq!(request.version(), Version::HTTP_2);
+    assert_eq!(request.headers()["content-type"], "text/plain");
+    assert_eq!(request.headers()["user-agent"], "app/1.0");
+    assert_eq!(request.body(), "hello");
+
+    let mut layer = HeaderMap::new();
+    layer.append("x-a", HeaderValue::from_static("1"));
+    layer.append("x-a", HeaderValue::from_static("2"));
+    let ser = postcard::to_allocvec(&RequestWrapper({
+        let mut request = Request::new(String::new());
+        *request.headers_mut() = layer;
+        request
+    }))
+    .unwrap();
+    let mut request = template.clone();
+    let res = http_serde_ext::Limits::new().max_headers(1).scope(|| {
+        http_serde_ext::request::MergeInto::new(&mut request)
+            .deserialize(&mut postcard::Deserializer::from_bytes(&ser))
+    });
+    assert!(res.is_err());
+    assert_eq!(request.headers(), template.headers());
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header("cache-control", "no-store")
+        .body(Vec::<u8>::new())
+        .unwrap();
+    let json = r#"{"head":{"status":503,"headers":{"retry-after":"30"}},"body":[1,2]}"#;
+    http_serde_ext::response::MergeInto::new(&mut response)
+        .deserialize(&mut serde_json::Deserializer::from_str(json))
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()["cache-control"], "no-store");
+    assert_eq!(response.headers()["retry-after"], "30");
+    assert_eq!(response.body(), &[1, 2]);
+
+    let res = http_serde_ext::response::MergeInto::new(&mut response).deserialize(
+        &mut serde_json::Deserializer::from_str(r#"{"head":{"status":1000}}"#),
+    );
+    assert!(res.is_err());
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[test]
+fn test_header_patch() {
+    use http_serde_ext::{HeaderOp, HeaderPatch};
+
+    let patch = HeaderPatch::new()
+        .set(
+            HeaderName::from_static("x-env"),
+            HeaderValue::from_static("prod"),
+        )
+        .append(
+            HeaderName::from_static("via"),
+            HeaderValue::from_bytes(b"\xff").unwrap(),
+        )
+        .remove(HeaderName::from_static("server"))
+        .rename(
+            HeaderName::from_static("x-old"),
+            HeaderName::from_static("x-new"),
+        );
+    assert_eq!(patch.ops().len(), 4);
+
+    let json = json!([
+        {"set": {"name": "x-env", "value": "prod"}},
+        {"append": {"name": "via", "value": {"base64": "/w=="}}},
+        {"remove": {"name": "server"}},
+        {"rename": {"from": "x-old", "to": "x-new"}}
+    ]);
+    assert_eq!(serde_json::to_value(&patch).unwrap(), json);
+    assert_eq!(serde_json::from_value::<HeaderPatch>(json).unwrap(), patch);
+    let yaml = serde_yaml::to_string(&patch).unwrap();
+    assert!(yaml.starts_with("- set:\n    name: x-env\n"));
+    assert_eq!(serde_yaml::from_str::<HeaderPatch>(&yaml).unwrap(), patch);
+    let ser = serde_cbor::to_vec(&patch).unwrap();
+    assert_eq!(serde_cbor::from_slice::<HeaderPatch>(&ser).unwrap(), patch);
+    let ser = bincode::serialize(&patch).unwrap();
+    assert_eq!(bincode::deserialize::<HeaderPatch>(&ser).unwrap(), patch);
+    let ser = postcard::to_allocvec(&patch).unwrap();
+    assert_eq!(postcard::from_bytes::<HeaderPatch>(&ser).unwrap(), patch);
+
+    let mut headers = HeaderMap::new();
+    headers.append("x-env", HeaderValue::from_static("dev"));
+    headers.append("x-env", HeaderValue::from_static("test"));
+    headers.append("via", HeaderValue::from_static("1.0 cache"));
+    headers.append("server", HeaderValue::from_static("origin"));
+    headers.append("x-old", HeaderValue::from_static("a"));
+    headers.append("x-old", HeaderValue::from_static("b"));
+    headers.append("x-new", HeaderValue::from_static("stale"));
+    let original = headers.clone();
+
+    patch.apply(&mut headers);
+    assert_eq!(
+        headers.get_all("x-env").iter().collect::<Vec<_>>(),
+        ["prod"]
+    );
+    assert_eq!(
+        headers.get_all("via").iter().collect::<Vec<_>>(),
+        [
+            &HeaderValue::from_static("1.0 cache"),
+            &HeaderValue::from_bytes(b"\xff").unwrap()
+        ]
+    );
+    assert!(!headers.contains_key("server"));
+    assert!(!headers.contains_key("x-old"));
+    assert_eq!(
+        headers.get_all("x-new").iter().collect::<Vec<_>>(),
+        ["a", "b"]
+    );
+
+    let inverse = patch.invert(&original);
+    inverse.apply(&mut headers);
+    assert_eq!(headers, original);
+
+    // Renaming or removing a missing header does nothing.
+    let mut empty = HeaderMap::new();
+    HeaderPatch::new()
+        .remove(HeaderName::from_static("server"))
+        .rename(
+            HeaderName::from_static("x-old"),
+            HeaderName::from_static("x-new"),
+        )
+        .apply(&mut empty);
+    assert!(empty.is_empty());
+    assert!(HeaderPatch::diff(&empty, &empty).is_empty());
+
+    let mut to = original.clone();
+    to.remove("server");
+    to.insert("x-env", HeaderValue::from_static("prod"));
+    to.append("x-region", HeaderValue::from_static("eu"));
+    to.append("x-region", HeaderValue::from_static("us"));
+    let diff = HeaderPatch::diff(&original, &to);
+    assert_eq!(
+        diff,
+        HeaderPatch::from(vec![
+            HeaderOp::Remove {
+                name: HeaderName::from_static("server")
+            },
+            HeaderOp::Set {
+                name: HeaderName::from_static("x-env"),
+                value: HeaderValue::from_static("prod")
+            },
+            HeaderOp::Set {
+                name: HeaderName::from_static("x-region"),
+                value: HeaderValue::from_static("eu")
+            },
+            HeaderOp::Append {
+                name: HeaderName::from_static("x-region"),
+                value: HeaderValue::from_static("us")
+            },
+        ])
+    );
+    let mut patched = original.clone();
+    diff.apply(&mut patched);
+    assert_eq!(patched, to);
+
+    for _ in 0..10 {
+        let from: HeaderMap = Faker.fake();
+        let to: HeaderMap = Faker.fake();
+        let mut patched = from.clone();
+        HeaderPatch::diff(&from, &to).apply(&mut patched);
+        assert_eq!(patched, to);
+    }
+
+    let res = serde_json::from_value::<HeaderPatch>(json!([{"set": {"name": "x-a"}}]));
+    assert_eq!(res.unwrap_err().to_string(), "missing field `value`");
+    let res = serde_json::from_value::<HeaderPatch>(json!([{"replace": {"name": "x-a"}}]));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("unknown variant `replace`"));
+    let res = serde_json::from_value::<HeaderPatch>(json!([{
+        "remove": {"name": "x-a"},
+        "rename": {"from": "x-a", "to": "x-b"}
+    }]));
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "expected a single header operation"
+    );
+}
+
+#[test]
+fn test_redaction() {
+    use http_serde_ext::RedactionPolicy;
+
+    let mut secret = HeaderValue::from_static("hunter2");
+    secret.set_sensitive(true);
+
+    let mut map = HeaderMap::new();
+    map.insert("authorization", HeaderValue::from_static("Bearer secret"));
+    map.append("set-cookie", HeaderValue::from_static("a=b"));
+    map.append("set-cookie", HeaderValue::from_static("c=d"));
+    map.insert("x-password", secret);
+    map.insert("x-tenant", HeaderValue::from_static("acme"));
+    map.insert("accept", HeaderValue::from_static("*/*"));
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "http_serde_ext::header_map::redacted")] HeaderMap);
+
+    let ser = serde_json::to_value(Wrapper(map.clone())).unwrap();
+    assert_eq!(
+        ser,
+        json!({
+            "authorization": "<redacted>",
+            "set-cookie": ["<redacted>", "<redacted>"],
+            "x-password": "<redacted>",
+            "x-tenant": "acme",
+            "accept": "*/*"
+        })
+    );
+    let res = serde_json::from_value::<Wrapper>(ser);
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "header authorization is redacted"
+    );
+
+    let ser = bincode::serialize(&Wrapper(map.clone())).unwrap();
+    let res = bincode::deserialize::<Wrapper>(&ser);
+    assert!(res.is_err());
+
+    let mut clean = map.clone();
+    clean.remove("authorization");
+    clean.remove("set-cookie");
+    clean.remove("x-password");
+    let ser = serde_json::to_value(Wrapper(clean.clone())).unwrap();
+    let de: Wrapper = serde_json::from_value(ser).unwrap();
+    assert_eq!(de.0, clean);
+
+    let redacted = RedactionPolicy::new().redact(&map);
+    assert_eq!(redacted["authorization"], "<redacted>");
+    assert_eq!(redacted["x-tenant"], "acme");
+    assert!(RedactionPolicy::is_placeholder(&redacted["authorization"]));
+    assert!(!RedactionPolicy::is_placeholder(&redacted["accept"]));
+
+    #[cfg(feature = "sha2")]
+    {
+        let policy = RedactionPolicy::empty()
+            .deny(HeaderName::from_static("x-tenant"))
+            .hash_prefix(12);
+        assert_eq!(
+            serde_json::to_value(policy.header_map(&map)).unwrap(),
+            json!({
+                "authorization": "Bearer secret",
+                "set-cookie": ["a=b", "c=d"],
+                "x-password": "hunter2",
+                "x-tenant": "<redacted sha256:822b33ad87c1>",
+                "accept": "*/*"
+            })
+        );
+        let policy = RedactionPolicy::new()
+            .redact_sensitive(false)
+            .hash_prefix(100);
+        let redacted = policy.redact(&map);
+        assert_eq!(redacted["x-password"], "hunter2");
+        assert_eq!(
+            redacted["authorization"],
+            "<redacted sha256:bffde20413347b7a00e1363de3f97ca69e419dc0aea55f4a4a75018fab3a0e8e>"
+        );
+        assert!(RedactionPolicy::is_placeholder(&redacted["authorization"]));
+
+        let mut long = HeaderMap::new();
+        long.insert("x-a", HeaderValue::from_str(&"x".repeat(56)).unwrap());
+        long.insert("x-b", HeaderValue::from_str(&"y".repeat(130)).unwrap());
+        let long = RedactionPolicy::empty()
+            .deny(HeaderName::from_static("x-a"))
+            .deny(HeaderName::from_static("x-b"))
+            .hash_prefix(16)
+            .redact(&long);
+        assert_eq!(long["x-a"], "<redacted sha256:04c26261370ee754>");
+        assert_eq!(long["x-b"], "<redacted sha256:363f2674b4232b02>");
+        assert!(!RedactionPolicy::is_placeholder(&redacted["accept"]));
+    }
+
+    let mut request = Request::new("body");
+    *request.headers_mut() = map.clone();
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct RequestWrapper(#[serde(with = "http_serde_ext::request::redacted")] Request<String>);
+
+    let ser = serde_json::to_value(RequestWrapper(request.clone().map(String::from))).unwrap();
+    assert_eq!(ser["head"]["headers"]["authorization"], json!("<redacted>"));
+    assert_eq!(ser["head"]["headers"]["x-tenant"], json!("acme"));
+    assert_eq!(ser["body"], json!("body"));
+    let res = serde_json::from_value::<RequestWrapper>(ser);
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "header authorization is redacted"
+    );
+
+    let policy = RedactionPolicy::new().deny(HeaderName::from_static("x-tenant"));
+    let ser = serde_json::to_value(policy.request(&request)).unwrap();
+    assert_eq!(ser["head"]["headers"]["x-tenant"], json!("<redacted>"));
+    assert_eq!(ser["head"]["method"], json!("GET"));
+
+    let mut response = Response::new(());
+    *response.headers_mut() = map.clone();
+
+    #[derive(Serialize, Deserialize)]
+    struct ResponseWrapper(#[serde(with = "http_serde_ext::response::redacted")] Response<()>);
+
+    let ser = serde_yaml::to_string(&ResponseWrapper(response.clone())).unwrap();
+    assert!(ser.contains("set-cookie:\n    - <redacted>"), "{ser}");
+    assert!(serde_yaml::from_str::<ResponseWrapper>(&ser).is_err());
+
+    let ser = serde_json::to_value(policy.response(&response)).unwrap();
+    assert_eq!(ser["head"]["headers"]["x-tenant"], json!("<redacted>"));
+    assert_eq!(ser["head"]["status"], json!(200));
+}
+
 #[test]
 fn test_method_roundtrip() {
     test_all!(
         Method,
-        Method::default(),
-        json!("GET"),
-        "GET",
-        "http_serde_ext::method",
-        "http_serde_ext::method::option",
-        "http_serde_ext::method::result",
-        "http_serde_ext::method::vec",
-        "http_serde_ext::method::vec_deque",
-        "http_serde_ext::method::linked_list",
-        "http_serde_ext::method::hash_map",
-        "http_serde_ext::method::btree_map"
+        Method::default(),
+        json!("GET"),
+        "GET",
+        "http_serde_ext::method",
+        "http_serde_ext::method::option",
+        "http_serde_ext::method::result",
+        "http_serde_ext::method::vec",
+        "http_serde_ext::method::vec_deque",
+        "http_serde_ext::method::linked_list",
+        "http_serde_ext::method::hash_map",
+        "http_serde_ext::method::btree_map"
+    );
+
+    test_hash!(
+        Method,
+        Method::default(),
+        json!("GET"),
+        "GET",
+        "http_serde_ext::method::hash_map_key",
+        "http_serde_ext::method::hash_set"
+    );
+
+    let fake: Method = Faker.fake();
+    test_all_no_intermediate_compare!(
+        Method,
+        fake.clone(),
+        "http_serde_ext::method",
+        "http_serde_ext::method::option",
+        "http_serde_ext::method::result",
+        "http_serde_ext::method::vec",
+        "http_serde_ext::method::vec_deque",
+        "http_serde_ext::method::linked_list",
+        "http_serde_ext::method::hash_map",
+        "http_serde_ext::method::btree_map"
+    );
+
+    test_hash_no_intermediate_compare!(
+        Method,
+        fake.clone(),
+        "http_serde_ext::method::hash_map_key",
+        "http_serde_ext::method::hash_set"
+    );
+}
+
+#[test]
+fn test_compact_binary_encoding() {
+    #[derive(Serialize, Deserialize)]
+    struct VersionWrapper(#[serde(with = "http_serde_ext::version")] Version);
+
+    #[derive(Serialize, Deserialize)]
+    struct MethodWrapper(#[serde(with = "http_serde_ext::method")] Method);
+
+    for version in [
+        Version::HTTP_09,
+        Version::HTTP_10,
+        Version::HTTP_11,
+        Version::HTTP_2,
+        Version::HTTP_3,
+    ] {
+        assert_eq!(
+            bincode::serialize(&VersionWrapper(version)).unwrap().len(),
+            1
+        );
+        assert_eq!(
+            postcard::to_allocvec(&VersionWrapper(version))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    for method in [
+        Method::GET,
+        Method::POST,
+        Method::PUT,
+        Method::DELETE,
+        Method::HEAD,
+        Method::OPTIONS,
+        Method::CONNECT,
+        Method::PATCH,
+        Method::TRACE,
+    ] {
+        assert_eq!(
+            bincode::serialize(&MethodWrapper(method.clone()))
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            postcard::to_allocvec(&MethodWrapper(method.clone()))
+                .unwrap()
+                .len(),
+            2
+        );
+        no_intermediate_compare_roundtrip!(Method, method.clone(), "http_serde_ext::method");
+    }
+
+    let extension = Method::from_bytes(b"PROPFIND").unwrap();
+    assert_eq!(
+        postcard::to_allocvec(&MethodWrapper(extension.clone())).unwrap(),
+        b"\xff\x01\x08PROPFIND"
+    );
+    roundtrip!(
+        Method,
+        extension.clone(),
+        "http_serde_ext::method",
+        json!("PROPFIND"),
+        "PROPFIND\n"
+    );
+
+    let res = bincode::deserialize::<MethodWrapper>(&[9, 0]);
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        "invalid value: integer `9`, expected valid method name"
+    );
+    let res = bincode::deserialize::<MethodWrapper>(b"\x00\x01\x03\0\0\0\0\0\0\0GET");
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        "standard method with a name"
+    );
+    let res = bincode::deserialize::<MethodWrapper>(b"\xff\x00");
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        "extension method without a name"
+    );
+    let res = bincode::deserialize::<VersionWrapper>(&[5]);
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        "invalid value: integer `5`, expected a version string"
+    );
+}
+
+#[test]
+fn test_method_case_insensitive() {
+    test_all!(
+        Method,
+        Method::POST,
+        json!("POST"),
+        "POST",
+        "http_serde_ext::method::case_insensitive",
+        "http_serde_ext::method::case_insensitive::option",
+        "http_serde_ext::method::case_insensitive::result",
+        "http_serde_ext::method::case_insensitive::vec",
+        "http_serde_ext::method::case_insensitive::vec_deque",
+        "http_serde_ext::method::case_insensitive::linked_list",
+        "http_serde_ext::method::case_insensitive::hash_map",
+        "http_serde_ext::method::case_insensitive::btree_map"
     );
 
     test_hash!(
         Method,
-        Method::default(),
-        json!("GET"),
-        "GET",
-        "http_serde_ext::method::hash_map_key",
-        "http_serde_ext::method::hash_set"
+        Method::POST,
+        json!("POST"),
+        "POST",
+        "http_serde_ext::method::case_insensitive::hash_map_key",
+        "http_serde_ext::method::case_insensitive::hash_set"
     );
 
-    let fake: Method = Faker.fake();
-    test_all_no_intermediate_compare!(
-        Method,
-        fake.clone(),
-        "http_serde_ext::method",
-        "http_serde_ext::method::option",
-        "http_serde_ext::method::result",
-        "http_serde_ext::method::vec",
-        "http_serde_ext::method::vec_deque",
-        "http_serde_ext::method::linked_list",
-        "http_serde_ext::method::hash_map",
-        "http_serde_ext::method::btree_map"
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(with = "http_serde_ext::method::case_insensitive")] Method);
+
+    for (json, method) in [
+        (json!("get"), Method::GET),
+        (json!("Post"), Method::POST),
+        (json!("dElEtE"), Method::DELETE),
+        (json!("options"), Method::OPTIONS),
+    ] {
+        let de: Wrapper = serde_json::from_value(json).unwrap();
+        assert_eq!(de.0, method);
+    }
+
+    let de: Wrapper = serde_json::from_value(json!("propfind")).unwrap();
+    assert_eq!(de.0.as_str(), "propfind");
+    assert_ne!(de.0, Method::from_bytes(b"PROPFIND").unwrap());
+
+    #[derive(Deserialize)]
+    struct SetWrapper(
+        #[serde(with = "http_serde_ext::method::case_insensitive::hash_set")] HashSet<Method>,
     );
 
-    test_hash_no_intermediate_compare!(
-        Method,
-        fake.clone(),
-        "http_serde_ext::method::hash_map_key",
-        "http_serde_ext::method::hash_set"
+    let de: SetWrapper = serde_yaml::from_str("- get\n- GET\n- head\n").unwrap();
+    assert_eq!(de.0, HashSet::from([Method::GET, Method::HEAD]));
+
+    #[derive(Deserialize)]
+    struct KeyWrapper(
+        #[serde(with = "http_serde_ext::method::case_insensitive::hash_map_key")]
+        HashMap<Method, String>,
     );
+
+    let de: KeyWrapper = serde_json::from_value(json!({"put": "/upload"})).unwrap();
+    assert_eq!(de.0, HashMap::from([(Method::PUT, "/upload".to_string())]));
 }
 
 macro_rules! serde_json_roundtrip_res_req {
@@ -992,6 +2918,233 @@ fn test_response_roundtrip() {
     );
 }
 
+#[cfg(feature = "headers")]
+#[test]
+fn test_typed_header() {
+    use headers::{
+        authorization::Bearer, AccessControlAllowMethods, Authorization, CacheControl, ContentType,
+        ETag,
+    };
+
+    test_all!(
+        ContentType,
+        ContentType::json(),
+        json!("application/json"),
+        "application/json",
+        "http_serde_ext::typed_header",
+        "http_serde_ext::typed_header::option",
+        "http_serde_ext::typed_header::result",
+        "http_serde_ext::typed_header::vec",
+        "http_serde_ext::typed_header::vec_deque",
+        "http_serde_ext::typed_header::linked_list",
+        "http_serde_ext::typed_header::hash_map",
+        "http_serde_ext::typed_header::btree_map"
+    );
+
+    let etag: ETag = "\"xyzzy\"".parse().unwrap();
+    roundtrip!(
+        ETag,
+        etag.clone(),
+        "http_serde_ext::typed_header",
+        json!("\"xyzzy\""),
+        "'\"xyzzy\"'\n"
+    );
+
+    let cache_control = CacheControl::new()
+        .with_no_cache()
+        .with_max_age(std::time::Duration::from_secs(60));
+    roundtrip!(
+        CacheControl,
+        cache_control.clone(),
+        "http_serde_ext::typed_header",
+        json!("no-cache, max-age=60"),
+        "no-cache, max-age=60\n"
+    );
+
+    let bearer = Authorization::bearer("secret").unwrap();
+    no_intermediate_compare_roundtrip!(
+        Authorization<Bearer>,
+        bearer.clone(),
+        "http_serde_ext::typed_header"
+    );
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Methods(#[serde(with = "http_serde_ext::typed_header")] AccessControlAllowMethods);
+
+    let de: Methods = serde_json::from_value(json!(["GET, PUT", "POST"])).unwrap();
+    assert_eq!(
+        de.0.iter().collect::<Vec<_>>(),
+        [Method::GET, Method::PUT, Method::POST]
+    );
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct BearerAuth(#[serde(with = "http_serde_ext::typed_header")] Authorization<Bearer>);
+
+    let res = serde_json::from_value::<BearerAuth>(json!("Basic Zm9vOmJhcg=="));
+    assert_eq!(res.unwrap_err().to_string(), "invalid authorization header");
+    let res = serde_json::from_value::<BearerAuth>(json!([]));
+    assert_eq!(res.unwrap_err().to_string(), "invalid authorization header");
+    let res = serde_json::from_value::<BearerAuth>(json!(1));
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "invalid type: integer `1`, expected a value or a sequence of values for header authorization"
+    );
+    let res = serde_json::from_value::<BearerAuth>(json!(["Bearer a", "a\nb"]));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("invalid value for header authorization: "));
+}
+
+#[cfg(feature = "hyper")]
+#[test]
+fn test_response_reason_phrase_roundtrip() {
+    use hyper::ext::ReasonPhrase;
+
+    fn equate<T: Debug + Eq>(a: &Response<T>, b: &Response<T>) {
+        assert_eq!(a.body(), b.body());
+        assert_eq!(a.status(), b.status());
+        assert_eq!(a.headers(), b.headers());
+        assert_eq!(a.version(), b.version());
+        assert_eq!(
+            a.extensions().get::<ReasonPhrase>(),
+            b.extensions().get::<ReasonPhrase>()
+        );
+        assert_eq!(a.extensions().len(), b.extensions().len());
+    }
+
+    let mut response = Response::new(());
+    response
+        .extensions_mut()
+        .insert(ReasonPhrase::from_static(b"Alright"));
+
+    roundtrip_res_req!(
+        Response<()>,
+        response.clone(),
+        equate,
+        "http_serde_ext::response",
+        json!({
+            "head": {
+                "status": 200,
+                "headers": {},
+                "version": "HTTP/1.1",
+                "reason": "Alright"
+            },
+            "body": null
+        }),
+        "head:\n  status: 200\n  headers: {}\n  version: HTTP/1.1\n  reason: Alright\nbody: null\n"
+    );
+
+    let response: Response<String> = Faker.fake();
+    no_intermediate_compare_roundtrip_res_req!(
+        Response<String>,
+        response.clone(),
+        equate,
+        "http_serde_ext::response"
+    );
+
+    let mut response = Response::new(());
+    response.extensions_mut().insert(true);
+    response
+        .extensions_mut()
+        .insert(ReasonPhrase::from_static(b"Alright"));
+
+    #[derive(Serialize)]
+    struct Wrapper(#[serde(with = "http_serde_ext::response")] Response<()>);
+
+    let result = serde_json::to_value(Wrapper(response));
+    assert_eq!(result.unwrap_err().to_string(), "extensions is not empty");
+
+    // A new status drops a reason that came with the old one.
+    use serde::de::DeserializeSeed;
+
+    let mut template = Response::new(());
+    template
+        .extensions_mut()
+        .insert(ReasonPhrase::from_static(b"Alright"));
+    let merge = |json: &str| {
+        let mut response = template.clone();
+        http_serde_ext::response::MergeInto::new(&mut response)
+            .deserialize(&mut serde_json::Deserializer::from_str(json))
+            .unwrap();
+        response
+    };
+    let response = merge(r#"{"head":{"status":404}}"#);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.extensions().get::<ReasonPhrase>().is_none());
+    let response = merge(r#"{"head":{"status":404,"reason":"Gone Fishing"}}"#);
+    assert_eq!(
+        response.extensions().get::<ReasonPhrase>(),
+        Some(&ReasonPhrase::from_static(b"Gone Fishing"))
+    );
+    let response = merge(r#"{"head":{"headers":{"x-a":"1"}}}"#);
+    assert_eq!(
+        response.extensions().get::<ReasonPhrase>(),
+        Some(&ReasonPhrase::from_static(b"Alright"))
+    );
+}
+
+#[test]
+fn test_response_binary_reason_slot() {
+    struct Bytes(&'static [u8]);
+
+    impl Serialize for Bytes {
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            ser.serialize_bytes(self.0)
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Head {
+        #[serde(with = "http_serde_ext::status_code")]
+        status: StatusCode,
+        #[serde(with = "http_serde_ext::header_map")]
+        headers: HeaderMap,
+        #[serde(with = "http_serde_ext::version")]
+        version: Version,
+        reason: Option<Bytes>,
+    }
+
+    #[derive(Serialize)]
+    struct Message {
+        head: Head,
+        body: (),
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "http_serde_ext::response")] Response<()>);
+
+    // The layout doesn't depend on the `hyper` feature.
+    let message = |reason| Message {
+        head: Head {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            version: Version::HTTP_11,
+            reason,
+        },
+        body: (),
+    };
+    assert_eq!(
+        bincode::serialize(&Wrapper(Response::new(()))).unwrap(),
+        bincode::serialize(&message(None)).unwrap()
+    );
+    assert_eq!(
+        postcard::to_allocvec(&Wrapper(Response::new(()))).unwrap(),
+        postcard::to_allocvec(&message(None)).unwrap()
+    );
+
+    let ser = bincode::serialize(&message(Some(Bytes(b"Alright")))).unwrap();
+    let de: Wrapper = bincode::deserialize(&ser).unwrap();
+    assert_eq!(de.0.status(), StatusCode::OK);
+    #[cfg(feature = "hyper")]
+    assert_eq!(
+        de.0.extensions().get::<hyper::ext::ReasonPhrase>(),
+        Some(&hyper::ext::ReasonPhrase::from_static(b"Alright"))
+    );
+    #[cfg(not(feature = "hyper"))]
+    assert!(de.0.extensions().is_empty());
+}
+
 #[test]
 fn test_request_roundtrip() {
     fn equate<T: std::fmt::Debug + Eq>(a: &Request<T>, b: &Request<T>) {
@@ -1106,6 +3259,339 @@ fn test_status_code_roundtrip() {
     );
 }
 
+#[test]
+fn test_status_code_with_reason_roundtrip() {
+    test_all!(
+        StatusCode,
+        StatusCode::NOT_FOUND,
+        json!("404 Not Found"),
+        "404 Not Found",
+        "http_serde_ext::status_code::with_reason",
+        "http_serde_ext::status_code::with_reason::option",
+        "http_serde_ext::status_code::with_reason::result",
+        "http_serde_ext::status_code::with_reason::vec",
+        "http_serde_ext::status_code::with_reason::vec_deque",
+        "http_serde_ext::status_code::with_reason::linked_list",
+        "http_serde_ext::status_code::with_reason::hash_map",
+        "http_serde_ext::status_code::with_reason::btree_map"
+    );
+
+    test_all!(
+        StatusCode,
+        StatusCode::from_u16(299).unwrap(),
+        json!("299"),
+        "'299'",
+        "http_serde_ext::status_code::with_reason",
+        "http_serde_ext::status_code::with_reason::option",
+        "http_serde_ext::status_code::with_reason::result",
+        "http_serde_ext::status_code::with_reason::vec",
+        "http_serde_ext::status_code::with_reason::vec_deque",
+        "http_serde_ext::status_code::with_reason::linked_list",
+        "http_serde_ext::status_code::with_reason::hash_map",
+        "http_serde_ext::status_code::with_reason::btree_map"
+    );
+
+    test_hash!(
+        StatusCode,
+        StatusCode::NOT_FOUND,
+        json!("404 Not Found"),
+        "404 Not Found",
+        "http_serde_ext::status_code::with_reason::hash_map_key",
+        "http_serde_ext::status_code::with_reason::hash_set"
+    );
+
+    test_ord!(
+        StatusCode,
+        StatusCode::NOT_FOUND,
+        json!("404 Not Found"),
+        "404 Not Found",
+        "http_serde_ext::status_code::with_reason::btree_map_key",
+        "http_serde_ext::status_code::with_reason::btree_set"
+    );
+
+    let fake: StatusCode = Faker.fake();
+    test_all_no_intermediate_compare!(
+        StatusCode,
+        fake,
+        "http_serde_ext::status_code::with_reason",
+        "http_serde_ext::status_code::with_reason::option",
+        "http_serde_ext::status_code::with_reason::result",
+        "http_serde_ext::status_code::with_reason::vec",
+        "http_serde_ext::status_code::with_reason::vec_deque",
+        "http_serde_ext::status_code::with_reason::linked_list",
+        "http_serde_ext::status_code::with_reason::hash_map",
+        "http_serde_ext::status_code::with_reason::btree_map"
+    );
+}
+
+#[test]
+fn test_status_code_object_roundtrip() {
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "http_serde_ext::status_code::object")] StatusCode);
+
+    let ser = serde_json::to_string(&Wrapper(StatusCode::NOT_FOUND)).unwrap();
+    assert_eq!(
+        ser,
+        r#"{"code":404,"reason":"Not Found","class":"client_error"}"#
+    );
+    let de: Wrapper = serde_json::from_str(&ser).unwrap();
+    assert_eq!(de.0, StatusCode::NOT_FOUND);
+
+    let ser = serde_json::to_string(&Wrapper(StatusCode::from_u16(299).unwrap())).unwrap();
+    assert_eq!(ser, r#"{"code":299,"reason":null,"class":"success"}"#);
+    let de: Wrapper = serde_json::from_str(&ser).unwrap();
+    assert_eq!(de.0.as_u16(), 299);
+
+    let fake: StatusCode = Faker.fake();
+    test_all_no_intermediate_compare!(
+        StatusCode,
+        fake,
+        "http_serde_ext::status_code::object",
+        "http_serde_ext::status_code::object::option",
+        "http_serde_ext::status_code::object::result",
+        "http_serde_ext::status_code::object::vec",
+        "http_serde_ext::status_code::object::vec_deque",
+        "http_serde_ext::status_code::object::linked_list",
+        "http_serde_ext::status_code::object::hash_map",
+        "http_serde_ext::status_code::object::btree_map"
+    );
+
+    no_intermediate_compare_roundtrip!(
+        HashSet<StatusCode>,
+        HashSet::from([fake]),
+        "http_serde_ext::status_code::object::hash_set"
+    );
+    no_intermediate_compare_roundtrip!(
+        BTreeSet<StatusCode>,
+        BTreeSet::from([fake]),
+        "http_serde_ext::status_code::object::btree_set"
+    );
+}
+
+#[test]
+fn test_status_code_from_str() {
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(with = "http_serde_ext::status_code")] StatusCode);
+
+    let de: Wrapper = serde_json::from_value(json!("404")).unwrap();
+    assert_eq!(de.0, StatusCode::NOT_FOUND);
+    let de: Wrapper = serde_json::from_value(json!("404 Not Found")).unwrap();
+    assert_eq!(de.0, StatusCode::NOT_FOUND);
+    let res = serde_yaml::from_str::<Wrapper>("'200 Alright'");
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        r#"reason "Alright" does not match status code 200"#
+    );
+    let res = serde_json::from_value::<Wrapper>(json!("299 Custom"));
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        r#"reason "Custom" does not match status code 299"#
+    );
+    let de: Wrapper = serde_cbor::from_slice(&serde_cbor::to_vec(&"304").unwrap()).unwrap();
+    assert_eq!(de.0, StatusCode::NOT_MODIFIED);
+    let de: Wrapper =
+        serde_json::from_value(json!({"code": 404, "class": "client_error"})).unwrap();
+    assert_eq!(de.0, StatusCode::NOT_FOUND);
+    let res = serde_json::from_value::<Wrapper>(json!({"code": 404, "class": "success"}));
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        r#"class "success" does not match status code 404"#
+    );
+    let res = serde_json::from_value::<Wrapper>(json!({"code": 404, "reason": "OK"}));
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        r#"reason "OK" does not match status code 404"#
+    );
+    let de: Wrapper = serde_json::from_value(json!({"code": 299, "reason": null})).unwrap();
+    assert_eq!(de.0.as_u16(), 299);
+
+    #[derive(Deserialize)]
+    struct VecWrapper(#[serde(with = "http_serde_ext::status_code::vec")] Vec<StatusCode>);
+
+    let de: VecWrapper =
+        serde_json::from_value(json!([500, "502", "503 Service Unavailable"])).unwrap();
+    assert_eq!(
+        de.0,
+        vec![
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::SERVICE_UNAVAILABLE
+        ]
+    );
+
+    #[derive(Deserialize)]
+    struct KeyWrapper(
+        #[serde(with = "http_serde_ext::status_code::btree_map_key")] BTreeMap<StatusCode, u8>,
+    );
+
+    let de: KeyWrapper = serde_json::from_value(json!({"404 Not Found": 1, "500": 2})).unwrap();
+    assert_eq!(
+        de.0,
+        BTreeMap::from([
+            (StatusCode::NOT_FOUND, 1),
+            (StatusCode::INTERNAL_SERVER_ERROR, 2)
+        ])
+    );
+}
+
+#[test]
+fn test_status_code_set() {
+    use http_serde_ext::StatusCodeSet;
+
+    let set: StatusCodeSet =
+        serde_json::from_value(json!(["5xx", 429, "408-409", "404 Not Found", "301"])).unwrap();
+    for code in [301, 404, 408, 409, 429, 500, 503, 599] {
+        assert!(set.contains(StatusCode::from_u16(code).unwrap()), "{code}");
+    }
+    for code in [200, 302, 403, 410, 430, 600] {
+        assert!(!set.contains(StatusCode::from_u16(code).unwrap()), "{code}");
+    }
+    assert_eq!(
+        serde_json::to_value(&set).unwrap(),
+        json!([301, 404, "408-409", 429, "5xx"])
+    );
+
+    let mut expected = StatusCodeSet::new();
+    expected.insert_class(4);
+    expected.insert_class(5);
+    expected.insert(StatusCode::OK);
+    expected.insert_range(StatusCode::MOVED_PERMANENTLY..=StatusCode::SEE_OTHER);
+    assert_eq!(
+        serde_json::to_value(&expected).unwrap(),
+        json!([200, "301-303", "4xx", "5xx"])
+    );
+
+    let de: StatusCodeSet = serde_yaml::from_str("- 4xx\n- 5xx\n- 200\n- 301 - 303\n").unwrap();
+    assert_eq!(de, expected);
+    let de: StatusCodeSet = serde_json::from_value(json!([
+        "207 Multi-Status",
+        "203 Non Authoritative Information"
+    ]))
+    .unwrap();
+    assert_eq!(
+        de.iter().collect::<Vec<_>>(),
+        vec![
+            StatusCode::NON_AUTHORITATIVE_INFORMATION,
+            StatusCode::MULTI_STATUS
+        ]
+    );
+    let de: StatusCodeSet = serde_json::from_value(json!("5xx")).unwrap();
+    assert!(de.contains(StatusCode::BAD_GATEWAY));
+    let de: StatusCodeSet = serde_json::from_value(json!(429)).unwrap();
+    assert_eq!(
+        de.iter().collect::<Vec<_>>(),
+        vec![StatusCode::TOO_MANY_REQUESTS]
+    );
+
+    let de: StatusCodeSet =
+        serde_cbor::from_slice(&serde_cbor::to_vec(&expected).unwrap()).unwrap();
+    assert_eq!(de, expected);
+    let de: StatusCodeSet = bincode::deserialize(&bincode::serialize(&expected).unwrap()).unwrap();
+    assert_eq!(de, expected);
+    let de: StatusCodeSet =
+        postcard::from_bytes(&postcard::to_allocvec(&expected).unwrap()).unwrap();
+    assert_eq!(de, expected);
+
+    let empty = StatusCodeSet::default();
+    assert!(empty.is_empty());
+    assert_eq!(serde_json::to_value(&empty).unwrap(), json!([]));
+
+    for (json, msg) in [
+        (
+            json!(["0xx"]),
+            "invalid value: string \"0xx\", expected a status code, class or range",
+        ),
+        (
+            json!(["504-500"]),
+            "invalid value: string \"504-500\", expected a status code, class or range",
+        ),
+        (json!([1000]), "invalid status code"),
+        (
+            json!([70000]),
+            "invalid value: integer `70000`, expected a status code",
+        ),
+    ] {
+        let res = serde_json::from_value::<StatusCodeSet>(json);
+        assert_eq!(res.unwrap_err().to_string(), msg);
+    }
+}
+
+#[test]
+fn test_ordered() {
+    use http_serde_ext::Ordered;
+
+    fn roundtrip<T>(val: T, json: serde_json::Value)
+    where
+        T: Serialize + for<'a> Deserialize<'a> + Debug + PartialEq,
+    {
+        assert_eq!(serde_json::to_value(&val).unwrap(), json);
+        assert_eq!(serde_json::from_value::<T>(json).unwrap(), val);
+        let ser = serde_yaml::to_string(&val).unwrap();
+        assert_eq!(serde_yaml::from_str::<T>(&ser).unwrap(), val);
+        let ser = serde_cbor::to_vec(&val).unwrap();
+        assert_eq!(serde_cbor::from_slice::<T>(&ser).unwrap(), val);
+        let ser = bincode::serialize(&val).unwrap();
+        assert_eq!(bincode::deserialize::<T>(&ser).unwrap(), val);
+        let ser = postcard::to_allocvec(&val).unwrap();
+        assert_eq!(postcard::from_bytes::<T>(&ser).unwrap(), val);
+    }
+
+    let methods = BTreeMap::from([
+        (Ordered(Method::POST), 1),
+        (Ordered(Method::GET), 2),
+        (Ordered(Method::from_bytes(b"PURGE").unwrap()), 3),
+    ]);
+    roundtrip(methods, json!({"GET": 2, "POST": 1, "PURGE": 3}));
+
+    let names = BTreeSet::from([
+        Ordered(HeaderName::from_static("x-b")),
+        Ordered(HeaderName::from_static("accept")),
+    ]);
+    roundtrip(names, json!(["accept", "x-b"]));
+
+    let paths = BTreeSet::from([
+        Ordered(PathAndQuery::from_static("/b")),
+        Ordered(PathAndQuery::from_static("/a?z")),
+    ]);
+    roundtrip(paths, json!(["/a?z", "/b"]));
+
+    let mut authorities = BTreeSet::from([
+        Ordered(Authority::from_static("b.com")),
+        Ordered(Authority::from_static("A.com")),
+    ]);
+    assert!(!authorities.insert(Ordered(Authority::from_static("a.COM"))));
+    roundtrip(authorities, json!(["A.com", "b.com"]));
+
+    let schemes = BTreeSet::from([
+        Ordered(Scheme::HTTPS),
+        Ordered(Scheme::HTTP),
+        Ordered(Scheme::from_str("FTP").unwrap()),
+    ]);
+    roundtrip(schemes, json!(["FTP", "http", "https"]));
+
+    let uris = [
+        "/a",
+        "/a?b",
+        "/b",
+        "http://A.com/b",
+        "http://a.com/c",
+        "http://b.com/",
+        "https://a.com/",
+    ]
+    .map(|uri| Ordered(Uri::from_static(uri)));
+    for pair in uris.windows(2) {
+        assert!(pair[0] < pair[1], "{:?} {:?}", pair[0], pair[1]);
+    }
+    assert_eq!(
+        Ordered(Uri::from_static("HTTP://A.com/")).cmp(&Ordered(Uri::from_static("http://a.COM/"))),
+        std::cmp::Ordering::Equal
+    );
+    roundtrip(
+        BTreeMap::from([(Ordered(Uri::from_static("/b")), 1), (uris[0].clone(), 0)]),
+        json!({"/a": 0, "/b": 1}),
+    );
+}
+
 #[test]
 fn test_uri_roundtrip() {
     test_all!(
@@ -1233,9 +3719,145 @@ fn test_version_roundtrip() {
     );
 }
 
+#[test]
+fn test_version_constants_roundtrip() {
+    for (version, text, alpn) in [
+        (Version::HTTP_09, "HTTP/0.9", "http/0.9"),
+        (Version::HTTP_10, "HTTP/1.0", "http/1.0"),
+        (Version::HTTP_11, "HTTP/1.1", "http/1.1"),
+        (Version::HTTP_2, "HTTP/2.0", "h2"),
+        (Version::HTTP_3, "HTTP/3.0", "h3"),
+    ] {
+        roundtrip!(
+            Version,
+            version,
+            "http_serde_ext::version",
+            json!(text),
+            format!("{text}\n")
+        );
+        roundtrip!(
+            Version,
+            version,
+            "http_serde_ext::version::strict",
+            json!(text),
+            format!("{text}\n")
+        );
+        roundtrip!(
+            Version,
+            version,
+            "http_serde_ext::version::alpn",
+            json!(alpn),
+            format!("{alpn}\n")
+        );
+    }
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Wrapper(#[serde(with = "http_serde_ext::version")] Version);
+
+    let message = "version is unknown to http-serde-ext and cannot be deserialized";
+    let res = bincode::deserialize::<Wrapper>(&[255]);
+    assert_eq!(res.err().unwrap().to_string(), message);
+    let res = postcard::from_bytes::<Wrapper>(&[255]);
+    assert!(res.is_err());
+    let res = serde_cbor::from_slice::<Wrapper>(&serde_cbor::to_vec(&255u8).unwrap());
+    assert_eq!(res.err().unwrap().to_string(), message);
+}
+
+#[test]
+fn test_version_alpn_roundtrip() {
+    test_all!(
+        Version,
+        Version::HTTP_2,
+        json!("h2"),
+        "h2",
+        "http_serde_ext::version::alpn",
+        "http_serde_ext::version::alpn::option",
+        "http_serde_ext::version::alpn::result",
+        "http_serde_ext::version::alpn::vec",
+        "http_serde_ext::version::alpn::vec_deque",
+        "http_serde_ext::version::alpn::linked_list",
+        "http_serde_ext::version::alpn::hash_map",
+        "http_serde_ext::version::alpn::btree_map"
+    );
+
+    test_hash!(
+        Version,
+        Version::HTTP_11,
+        json!("http/1.1"),
+        "http/1.1",
+        "http_serde_ext::version::alpn::hash_map_key",
+        "http_serde_ext::version::alpn::hash_set"
+    );
+
+    test_ord!(
+        Version,
+        Version::HTTP_3,
+        json!("h3"),
+        "h3",
+        "http_serde_ext::version::alpn::btree_map_key",
+        "http_serde_ext::version::alpn::btree_set"
+    );
+
+    let fake: Version = Faker.fake();
+    test_all_no_intermediate_compare!(
+        Version,
+        fake,
+        "http_serde_ext::version::alpn",
+        "http_serde_ext::version::alpn::option",
+        "http_serde_ext::version::alpn::result",
+        "http_serde_ext::version::alpn::vec",
+        "http_serde_ext::version::alpn::vec_deque",
+        "http_serde_ext::version::alpn::linked_list",
+        "http_serde_ext::version::alpn::hash_map",
+        "http_serde_ext::version::alpn::btree_map"
+    );
+}
+
+#[test]
+fn test_version_aliases() {
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(with = "http_serde_ext::version")] Version);
+
+    for (json, version) in [
+        (json!("HTTP/2"), Version::HTTP_2),
+        (json!("HTTP/3"), Version::HTTP_3),
+        (json!("http/1.1"), Version::HTTP_11),
+        (json!("http/1.0"), Version::HTTP_10),
+        (json!("http/0.9"), Version::HTTP_09),
+        (json!("h2"), Version::HTTP_2),
+        (json!("h2c"), Version::HTTP_2),
+        (json!("h3"), Version::HTTP_3),
+        (json!("1.1"), Version::HTTP_11),
+        (json!(1.1), Version::HTTP_11),
+        (json!(1.0), Version::HTTP_10),
+        (json!(0.9), Version::HTTP_09),
+        (json!(2), Version::HTTP_2),
+        (json!(3.0), Version::HTTP_3),
+    ] {
+        let de: Wrapper = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(de.0, version, "{json}");
+    }
+
+    let de: Wrapper = serde_yaml::from_str("1.1").unwrap();
+    assert_eq!(de.0, Version::HTTP_11);
+
+    #[derive(Deserialize)]
+    struct KeyWrapper(
+        #[serde(with = "http_serde_ext::version::hash_map_key")] HashMap<Version, u8>,
+    );
+
+    let de: KeyWrapper = serde_json::from_value(json!({"h2": 1, "HTTP/1.1": 2})).unwrap();
+    assert_eq!(
+        de.0,
+        HashMap::from([(Version::HTTP_2, 1), (Version::HTTP_11, 2)])
+    );
+}
+
 macro_rules! invalid_deserialize {
     ($ty:ty, $json:expr, $path:literal, $msg:tt) => {{
         #[derive(Deserialize)]
+        #[allow(dead_code)]
         struct Wrapper(#[serde(with = $path)] $ty);
 
         let res = serde_json::from_value::<Wrapper>($json);
@@ -1287,6 +3909,36 @@ fn test_invalid() {
         "http_serde_ext::header_map",
         "no value for header empty_key"
     );
+    invalid_deserialize!(
+        HeaderMap,
+        json!({"x-a": "ok", "x-b": invalid_str}),
+        "http_serde_ext::header_map",
+        "invalid value for header x-b: failed to parse header value"
+    );
+    invalid_deserialize!(
+        HeaderMap,
+        json!({"x-a": ["ok", invalid_str]}),
+        "http_serde_ext::header_map",
+        "invalid value for header x-a: failed to parse header value"
+    );
+    invalid_deserialize!(
+        HeaderMap,
+        json!({"x-a": 1}),
+        "http_serde_ext::header_map",
+        "invalid type: integer `1`, expected a value or a sequence of values for header x-a"
+    );
+    invalid_deserialize!(
+        HeaderMap,
+        json!({"x-a": [true]}),
+        "http_serde_ext::header_map",
+        "invalid type: boolean `true`, expected a value for header x-a"
+    );
+    invalid_deserialize!(
+        HeaderMap,
+        json!({"x-a": {"base64": "!"}}),
+        "http_serde_ext::header_map",
+        "invalid value for header x-a: invalid base64"
+    );
 
     invalid_deserialize!(
         HeaderName,
@@ -1330,6 +3982,24 @@ fn test_invalid() {
         "http_serde_ext::status_code",
         "invalid status code"
     );
+    invalid_deserialize!(
+        StatusCode,
+        json!(65736),
+        "http_serde_ext::status_code",
+        "invalid value: integer `65736`, expected a status code"
+    );
+    invalid_deserialize!(
+        StatusCode,
+        json!(-65336),
+        "http_serde_ext::status_code",
+        "invalid value: integer `-65336`, expected a status code"
+    );
+    invalid_deserialize!(
+        StatusCode,
+        json!("Not Found"),
+        "http_serde_ext::status_code",
+        "invalid value: string \"Not Found\", expected a status code"
+    );
     invalid_deserialize!(Uri, json!(""), "http_serde_ext::uri", "empty string");
     invalid_deserialize!(
         Version,
@@ -1337,4 +4007,16 @@ fn test_invalid() {
         "http_serde_ext::version",
         "invalid value: string \"HTTP/0.0\", expected a version string"
     );
+    invalid_deserialize!(
+        Version,
+        json!(1),
+        "http_serde_ext::version",
+        "invalid value: integer `1`, expected a version string"
+    );
+    invalid_deserialize!(
+        Version,
+        json!(1.2),
+        "http_serde_ext::version",
+        "invalid value: floating point `1.2`, expected a version string"
+    );
 }